@@ -0,0 +1,255 @@
+//! where games are played: on a server or in-process from a local dataset
+
+use chrono::NaiveDate;
+use nixdle::{
+  State, api, parse_builtin_types, parse_functions_filtered, parse_provenance,
+  provenance::Provenance,
+};
+use reqwest::Client;
+use std::{fs, path::Path};
+
+use crate::error::{Error, Result};
+
+/// which game to play
+pub enum Mode {
+  Daily,
+  /// an earlier day's game
+  Archive(NaiveDate),
+  /// a new random game every time
+  Practice,
+}
+
+pub enum Backend {
+  Remote {
+    client: Client,
+    url: String,
+    /// where attempts go, known after starting
+    attempt_url: String,
+    giveup_url: String,
+  },
+  Local(Box<State>),
+}
+
+impl Backend {
+  pub fn remote(url: &str) -> Self {
+    Backend::Remote {
+      client: Client::new(),
+      url: url.to_string(),
+      attempt_url: String::new(),
+      giveup_url: String::new(),
+    }
+  }
+
+  /// loads the dataset & picks the game of the day to play
+  /// the same dataset picks the same function as an unsalted server
+  pub fn local(data_dir: &Path, mode: &Mode) -> Result<Self> {
+    let today = chrono::Local::now().date_naive();
+    let date = match *mode {
+      Mode::Archive(date) if date >= today => return Err(Error::NotOver),
+      Mode::Archive(date) => date,
+      _ => today,
+    };
+
+    let read = |name: &str| fs::read_to_string(data_dir.join(name));
+    let builtin_types = parse_builtin_types(&read("builtin_types.json")?)?;
+    let functions = parse_functions_filtered(&builtin_types, &read("functions.json")?)?;
+    let provenance = match read("manifest.json") {
+      Ok(manifest) => parse_provenance(&manifest)?,
+      Err(_) => Provenance::default(),
+    };
+
+    let mut state = State::new(functions, builtin_types, provenance);
+    state.init_daily_game(date, "");
+    Ok(Backend::Local(Box::new(state)))
+  }
+
+  /// starts (or resumes) a game
+  pub async fn start(&mut self, mode: &Mode, session: Option<&str>) -> Result<api::StartMessage> {
+    match self {
+      Backend::Remote {
+        client,
+        url,
+        attempt_url,
+        giveup_url,
+      } => {
+        let start_url = match mode {
+          Mode::Daily => format!("{}/start", url),
+          Mode::Archive(date) => format!("{}/archive/{}/start", url, date),
+          Mode::Practice => format!("{}/practice/start", url),
+        };
+        let message = request_start(client, &start_url, session).await?;
+        *attempt_url = message.attempt_url.clone();
+        *giveup_url = message.giveup_url.clone();
+        Ok(message)
+      }
+      Backend::Local(state) => {
+        if let Mode::Practice = mode {
+          state.init_random_game();
+        }
+        Ok(state.start_game(String::new(), String::new(), String::new()))
+      }
+    }
+  }
+
+  /// judges an attempt
+  /// `attempts` is the number of valid attempts so far, the server keeps count on its own
+  pub async fn attempt(
+    &self,
+    data: &api::AttemptData,
+    attempts: usize,
+  ) -> Result<api::AttemptResponse> {
+    match self {
+      Backend::Remote {
+        client,
+        attempt_url,
+        ..
+      } => request_attempt(client, attempt_url, data).await,
+      Backend::Local(state) => Ok(state.attempt_game(&data.input, attempts)),
+    }
+  }
+
+  /// gives up on the game & reveals the answer
+  pub async fn give_up(&self, data: &api::GiveUpData) -> Result<api::RevealMessage> {
+    match self {
+      Backend::Remote {
+        client, giveup_url, ..
+      } => request_give_up(client, giveup_url, data).await,
+      Backend::Local(state) => Ok(state.reveal(state.game.as_ref().expect("where game??"))),
+    }
+  }
+
+  /// asks the server what a completion token vouches for, None if it's not valid
+  pub async fn verify(&self, token: &str) -> Result<Option<api::Completion>> {
+    match self {
+      Backend::Remote { client, url, .. } => {
+        request_verify(client, &format!("{}/verify", url), token).await
+      }
+      // there's no one to vouch for offline wins
+      Backend::Local(_) => Ok(None),
+    }
+  }
+
+  /// every path that can be guessed
+  pub async fn paths(&self) -> Result<Vec<String>> {
+    match self {
+      Backend::Remote { client, url, .. } => {
+        request_paths(client, &format!("{}/functions", url)).await
+      }
+      Backend::Local(state) => Ok(state.paths().to_vec()),
+    }
+  }
+
+  /// status text while an attempt is being judged
+  pub fn sending(&self) -> String {
+    match self {
+      Backend::Remote { attempt_url, .. } => format!("sending to {}", attempt_url),
+      Backend::Local(_) => "checking".to_string(),
+    }
+  }
+}
+
+async fn request_start(
+  client: &Client,
+  url: &str,
+  session: Option<&str>,
+) -> Result<api::StartMessage> {
+  let data = api::StartData {
+    session: session.map(str::to_string),
+  };
+  let res = client.get(url).query(&data).send().await?;
+
+  if res.status().is_client_error() {
+    return Err(Error::Server(res.text().await?));
+  }
+
+  res.json::<api::StartMessage>().await.map_err(Into::into)
+}
+
+async fn request_attempt(
+  client: &Client,
+  url: &str,
+  data: &api::AttemptData,
+) -> Result<api::AttemptResponse> {
+  let res = client.post(url).json(data).send().await?;
+
+  if res.status() == reqwest::StatusCode::CONFLICT {
+    return Err(conflict(res).await);
+  }
+  // e.g. a session the server has forgotten, it says so better than a status code
+  if res.status().is_client_error() {
+    return Err(Error::Server(res.text().await?));
+  }
+
+  res
+    .error_for_status()?
+    .json::<api::AttemptResponse>()
+    .await
+    .map_err(Into::into)
+}
+
+async fn request_give_up(
+  client: &Client,
+  url: &str,
+  data: &api::GiveUpData,
+) -> Result<api::RevealMessage> {
+  let res = client.post(url).json(data).send().await?;
+
+  if res.status() == reqwest::StatusCode::CONFLICT {
+    return Err(conflict(res).await);
+  }
+  // e.g. a session the server has forgotten, it says so better than a status code
+  if res.status().is_client_error() {
+    return Err(Error::Server(res.text().await?));
+  }
+
+  res
+    .error_for_status()?
+    .json::<api::RevealMessage>()
+    .await
+    .map_err(Into::into)
+}
+
+/// a conflict is a rollover or something the server explains itself
+async fn conflict(res: reqwest::Response) -> Error {
+  match res.text().await {
+    Ok(reason) if reason == api::ROLLED_OVER => Error::GameChanged,
+    Ok(reason) => Error::Server(reason),
+    Err(err) => err.into(),
+  }
+}
+
+async fn request_paths(client: &Client, url: &str) -> Result<Vec<String>> {
+  let res = client.get(url).send().await?;
+
+  res
+    .error_for_status()?
+    .json::<Vec<String>>()
+    .await
+    .map_err(Into::into)
+}
+
+async fn request_verify(
+  client: &Client,
+  url: &str,
+  token: &str,
+) -> Result<Option<api::Completion>> {
+  let data = api::VerifyData {
+    token: token.to_string(),
+  };
+  let res = client.post(url).json(&data).send().await?;
+
+  // servers from before signing don't vouch for anything
+  if matches!(
+    res.status(),
+    reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::NOT_FOUND
+  ) {
+    return Ok(None);
+  }
+
+  res
+    .error_for_status()?
+    .json::<api::Completion>()
+    .await
+    .map(Some)
+    .map_err(Into::into)
+}
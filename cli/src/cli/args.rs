@@ -2,29 +2,164 @@ use super::{
   THEME,
   theme::{BaseTheme, Theme},
 };
+use crate::{config::Config, error::Result, share::ShareFormat};
 use clap::Parser;
+use std::path::PathBuf;
 
 #[derive(clap::Parser)]
 #[command(version, about)]
 pub struct Cli {
-  /// api url to use
-  #[arg(long, value_name = "url", default_value = crate::DEFAULT_API_URL)]
-  pub api: String,
-  /// cli theme to use
-  #[arg(short, long, value_name = "theme", default_value_t, value_enum)]
-  theme: Theme,
+  /// api url to use (defaults to the official instance)
+  #[arg(long, value_name = "url")]
+  api: Option<String>,
+  /// play without a server, using a local dataset
+  #[arg(long, conflicts_with = "api")]
+  pub offline: bool,
+  /// dataset directory for offline play (defaults to the one built in, if any)
+  #[arg(long, value_name = "dir", requires = "offline")]
+  data: Option<PathBuf>,
+  /// cli theme to use [default: nix]
+  #[arg(short, long, value_name = "theme", value_enum)]
+  theme: Option<Theme>,
   /// don't show rules text
-  #[arg(long)]
+  #[arg(long, overrides_with = "show_rules")]
   pub hide_rules: bool,
+  /// show rules text, even if the config file hides it
+  #[arg(long, overrides_with = "hide_rules")]
+  show_rules: bool,
+  /// replay the nixdle from an earlier day (e.g. 2025-01-31)
+  #[arg(long, value_name = "date")]
+  pub date: Option<chrono::NaiveDate>,
+  /// how to draw the result grid for sharing [default: emoji]
+  #[arg(long, value_name = "format", value_enum)]
+  share_format: Option<ShareFormat>,
+  /// where to keep the progress of unfinished games (defaults to $XDG_STATE_HOME/nixdle)
+  #[arg(long, value_name = "dir")]
+  pub lockfile_dir: Option<PathBuf>,
+  /// copy the result grid to the clipboard (needs a terminal with osc 52 support)
+  #[arg(long)]
+  pub copy: bool,
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum Command {
+  /// show your stats & streaks
+  Stats,
+  /// play random functions, as many as you like (doesn't count towards stats)
+  Practice,
+  /// see the settings from the config file
+  Config {
+    #[command(subcommand)]
+    command: ConfigCommand,
+  },
+}
+
+#[derive(clap::Subcommand)]
+pub enum ConfigCommand {
+  /// print the settings in effect, with flags & defaults filled in
+  Show,
+}
+
+impl Cli {
+  pub fn api(&self) -> &str {
+    self.api.as_deref().unwrap_or(crate::DEFAULT_API_URL)
+  }
+
+  /// dataset directory for offline play, the built in one if none was given
+  pub fn data(&self) -> Option<PathBuf> {
+    self
+      .data
+      .clone()
+      .or_else(|| option_env!("NIXDLE_DATA_DIR").map(Into::into))
+  }
+
+  pub fn share_format(&self) -> ShareFormat {
+    self.share_format.unwrap_or_default()
+  }
+
+  fn theme(&self) -> Theme {
+    self.theme.unwrap_or_default()
+  }
+
+  /// fills in whatever wasn't passed as a flag from the config
+  fn merge(&mut self, config: Config) {
+    self.api = self.api.take().or(config.api);
+    self.data = self.data.take().or(config.data);
+    self.theme = self.theme.or(config.theme);
+    if !self.hide_rules && !self.show_rules {
+      self.hide_rules = config.hide_rules.unwrap_or_default();
+    }
+    self.share_format = self.share_format.or(config.share_format);
+    self.lockfile_dir = self.lockfile_dir.take().or(config.lockfile_dir);
+  }
+
+  /// the settings in effect, as they'd be written in the config file
+  pub fn effective(&self) -> Config {
+    Config {
+      api: Some(self.api().to_string()),
+      theme: Some(self.theme()),
+      hide_rules: Some(self.hide_rules),
+      lockfile_dir: Some(
+        self
+          .lockfile_dir
+          .clone()
+          .unwrap_or_else(crate::paths::state_dir),
+      ),
+      share_format: Some(self.share_format()),
+      data: self.data(),
+    }
+  }
 }
 
-pub fn parse() -> Cli {
-  let args = Cli::parse();
+pub fn parse() -> Result<Cli> {
+  let mut args = Cli::parse();
+  args.merge(Config::open()?);
+
+  *THEME.lock().unwrap() = BaseTheme::from_theme(args.theme());
 
-  match args.theme {
-    Theme::Nix => *THEME.lock().unwrap() = BaseTheme::from_theme(Theme::Nix),
-    Theme::Lix => *THEME.lock().unwrap() = BaseTheme::from_theme(Theme::Lix),
+  Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn flags_win_over_config() {
+    let mut args =
+      Cli::try_parse_from(["nixdle", "--api", "http://0.0.0.0:8000", "--show-rules"]).unwrap();
+    args.merge(Config {
+      api: Some("https://example.com".to_string()),
+      hide_rules: Some(true),
+      share_format: Some(ShareFormat::Ascii),
+      ..Config::default()
+    });
+
+    assert_eq!(args.api(), "http://0.0.0.0:8000");
+    assert!(!args.hide_rules);
+    assert!(matches!(args.share_format(), ShareFormat::Ascii));
+    assert!(matches!(args.theme(), Theme::Nix));
   }
 
-  args
+  #[test]
+  fn rules_flags() {
+    let merged = |flags: &[&str], hide_rules| {
+      let mut args = Cli::try_parse_from(["nixdle"].iter().chain(flags)).unwrap();
+      args.merge(Config {
+        hide_rules,
+        ..Config::default()
+      });
+      args.hide_rules
+    };
+
+    assert!(!merged(&[], None));
+    assert!(merged(&[], Some(true)));
+    assert!(merged(&["--hide-rules"], Some(false)));
+    assert!(!merged(&["--show-rules"], Some(true)));
+    // the last one wins
+    assert!(merged(&["--show-rules", "--hide-rules"], Some(false)));
+    assert!(!merged(&["--hide-rules", "--show-rules"], Some(true)));
+  }
 }
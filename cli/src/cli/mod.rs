@@ -1,10 +1,13 @@
 use console::{Term, style};
-use dialoguer::Input;
+use dialoguer::{Completion, Confirm, History, Input};
+use nixdle::api::{RevealMessage, TypeMatch};
 use std::sync::{LazyLock, Mutex};
 
 pub mod args;
-mod theme;
+pub mod theme;
 
+use crate::history::{HISTOGRAM_BARS, Stats};
+use crate::lockfile::Attempt;
 use theme::{BaseTheme, Theme};
 
 static TERM: LazyLock<Term> = LazyLock::new(Term::stdout);
@@ -25,19 +28,34 @@ macro_rules! ewrite_line {
   };
 }
 
-pub fn dialog(prompt: String) -> String {
+pub fn dialog(
+  prompt: String,
+  initial: Option<String>,
+  completion: &impl Completion,
+  history: &mut impl History<String>,
+) -> std::io::Result<String> {
   TERM.show_cursor().ok();
 
-  let input = Input::with_theme(&*THEME.lock().unwrap())
+  let theme = THEME.lock().unwrap();
+  let mut input = Input::with_theme(&*theme)
     .with_prompt(prompt)
-    .interact_text()
-    .expect("failed to read user input :c");
+    .completion_with(completion)
+    .history_with(history);
+  if let Some(initial) = initial {
+    input = input.with_initial_text(initial);
+  }
+
+  let input = input.interact_text();
+  if input.is_err() {
+    // the prompt is left unfinished
+    write_line!("");
+  }
 
   TERM.hide_cursor().ok();
-  input
+  input.map_err(Into::into)
 }
 
-pub fn print_welcome() {
+pub fn print_welcome(goal: String) {
   let name = env!("CARGO_BIN_NAME");
   let theme = THEME.lock().unwrap();
 
@@ -53,16 +71,110 @@ pub fn print_welcome() {
   );
 
   write_line!("\n{}", style("welcome to nixdle!").fg(theme.alt).bold());
-  write_line!("try to guess today's nix function\n");
+  write_line!("try to guess {}\n", goal);
 }
 
 pub fn print_rules(text: String) {
   clear_status();
   write_line!("{}", style(text).dim());
+  write_line!("{}", style("type :help for commands").dim());
   write_line!("{}\n", style("good luck!!").bold());
 }
 
-pub fn print_attempt(clues: Vec<String>, args: String, input: bool, output: bool) {
+pub fn print_commands(commands: &[(&str, &str)]) {
+  let theme = THEME.lock().unwrap();
+  clear_status();
+
+  for (name, what) in commands {
+    write_line!("  {:<9} {}", style(name).fg(theme.base), style(what).dim());
+  }
+  write_line!("");
+}
+
+pub fn print_history(attempts: &[Attempt]) {
+  let theme = THEME.lock().unwrap();
+  clear_status();
+
+  if attempts.is_empty() {
+    write_line!("no guesses yet\n");
+    return;
+  }
+
+  let width = attempts.iter().map(|a| a.guess.len()).max().unwrap_or(0);
+  for (i, attempt) in attempts.iter().enumerate() {
+    let Some(msg) = &attempt.feedback else {
+      write_line!(
+        "  {} {}",
+        style(format!("#{:<2}", i)).dim(),
+        style(&attempt.guess).bold()
+      );
+      continue;
+    };
+    write_line!(
+      "  {} {:<width$}  {} {}  {} {}  {} {}",
+      style(format!("#{:<2}", i)).dim(),
+      style(&msg.guess).bold(),
+      style("args:").fg(theme.base),
+      style(msg.args.to_string()).dim(),
+      style("inputs:").fg(theme.base),
+      msg
+        .inputs
+        .iter()
+        .map(|m| type_match(*m).to_string())
+        .collect::<Vec<_>>()
+        .join(" "),
+      style("output:").fg(theme.base),
+      type_match(msg.output),
+      width = width,
+    );
+  }
+  write_line!("");
+}
+
+/// redraws the board of a round left unfinished last time
+pub fn print_resumed(attempts: &[Attempt]) {
+  let base = THEME.lock().unwrap().base;
+  clear_status();
+
+  write_line!("{}\n", style("picking up where you left off").dim());
+  for (i, attempt) in attempts.iter().enumerate() {
+    write_line!(
+      "{} {}",
+      style(format!("guess#{}", i)).fg(base),
+      style(&attempt.guess).bold()
+    );
+    // lockfiles from before feedback was kept only have the guesses
+    if let Some(msg) = &attempt.feedback {
+      print_attempt(
+        msg.clues.clone(),
+        msg.args.to_string(),
+        &msg.inputs,
+        msg.output,
+      );
+    }
+  }
+}
+
+/// `next_in` is the number of attempts until the next clue, None if there are no more
+pub fn print_hint(clues: &[String], next_in: Option<usize>) {
+  let theme = THEME.lock().unwrap();
+  clear_status();
+
+  let path = match clues.is_empty() {
+    true => "???".to_string(),
+    false => format!("{}.???", clues.join(".")),
+  };
+  write_line!("  {} {}", style("path:").fg(theme.base), style(path).dim());
+
+  let next = match next_in {
+    Some(1) => "next clue after your next guess".to_string(),
+    Some(n) => format!("next clue in {} guesses", n),
+    None => "that's all the clues there are".to_string(),
+  };
+  write_line!("  {}\n", next);
+}
+
+pub fn print_attempt(clues: Vec<String>, args: String, inputs: &[TypeMatch], output: TypeMatch) {
   let theme = THEME.lock().unwrap();
 
   clear_status();
@@ -79,28 +191,65 @@ pub fn print_attempt(clues: Vec<String>, args: String, input: bool, output: bool
     style(args).dim()
   );
   write_line!(
-    "  {}  {}",
-    style("input type:").fg(theme.base),
-    if input {
-      style("✔").green().bold()
-    } else {
-      style("✘").red().bold()
-    }
+    "  {} {}",
+    style("input types:").fg(theme.base),
+    inputs
+      .iter()
+      .map(|m| type_match(*m).to_string())
+      .collect::<Vec<_>>()
+      .join(" ")
   );
   write_line!(
     "  {} {}",
     style("output type:").fg(theme.base),
-    if output {
-      style("✔").green().bold()
-    } else {
-      style("✘").red().bold()
-    }
+    type_match(output)
   );
 
   write_line!("");
 }
 
-pub fn print_solved(func: &str, desc: &str, attempts: usize, seconds: u64, date: &str) {
+fn type_match(m: TypeMatch) -> console::StyledObject<&'static str> {
+  match m {
+    TypeMatch::Exact => style("✔").green().bold(),
+    TypeMatch::Compatible => style("~").yellow().bold(),
+    TypeMatch::Wrong => style("✘").red().bold(),
+  }
+}
+
+pub fn print_suggestions(suggestions: &[String]) {
+  if let Some(names) = join_names(suggestions) {
+    write_line!("did you mean {}?", names);
+  }
+}
+
+pub fn print_ambiguous(paths: &[String]) {
+  if let Some(names) = join_names(paths) {
+    write_line!("which one? {}", names);
+  }
+}
+
+/// formats function names as "`a`, `b` or `c`"
+fn join_names(names: &[String]) -> Option<String> {
+  let names = names
+    .iter()
+    .map(|s| format!("`{}`", style(s).bold()))
+    .collect::<Vec<_>>();
+
+  match names.split_last() {
+    Some((last, [])) => Some(last.clone()),
+    Some((last, rest)) => Some(format!("{} or {}", rest.join(", "), last)),
+    None => None,
+  }
+}
+
+pub fn print_solved(
+  func: &str,
+  desc: &str,
+  attempts: usize,
+  seconds: u64,
+  date: &str,
+  replay: bool,
+) {
   let theme = THEME.lock().unwrap();
   clear_status();
 
@@ -119,15 +268,128 @@ pub fn print_solved(func: &str, desc: &str, attempts: usize, seconds: u64, date:
     style("description:").fg(theme.base),
   );
 
+  let which = match replay {
+    true => format!("the nixdle from {}", date),
+    false => "today's nixdle".to_string(),
+  };
   write_line!(
     "\n{}{}{}",
     style("whoa").green().bold(),
-    style(", you solved today's nixdle! ").green(),
+    style(format!(", you solved {}! ", which)).green(),
     style("congrats!").green().bold()
   );
   write_line!("here's your reward: 🍪");
 }
 
+pub fn print_stats(stats: &Stats) {
+  let theme = THEME.lock().unwrap();
+  clear_status();
+
+  if stats.played == 0 {
+    write_line!("no games played yet, go play one!");
+    return;
+  }
+
+  write_line!(
+    "{0} {5}         {1}\n{0} {6}       {2}%\n{0} {7} {3}\n{0} {8}     {4}",
+    " ",
+    style(stats.played),
+    style(stats.solved * 100 / stats.played),
+    style(stats.current_streak).bold(),
+    style(stats.max_streak),
+    style("played:").fg(theme.base),
+    style("win rate:").fg(theme.base),
+    style("current streak:").fg(theme.base),
+    style("max streak:").fg(theme.base),
+  );
+
+  write_line!("\n  {}", style("attempts:").fg(theme.base));
+  let most = stats.histogram.iter().copied().max().unwrap_or(0).max(1);
+  for (i, count) in stats.histogram.iter().enumerate() {
+    let label = match i + 1 {
+      HISTOGRAM_BARS => format!("{}+", HISTOGRAM_BARS),
+      n => n.to_string(),
+    };
+    write_line!(
+      "  {:>3} {} {}",
+      style(label).dim(),
+      style("█".repeat(count * 30 / most)).fg(theme.alt),
+      count
+    );
+  }
+}
+
+pub fn print_practice_solved(func: &str, desc: &str, attempts: usize, seconds: u64) {
+  let theme = THEME.lock().unwrap();
+  clear_status();
+
+  write_line!(
+    "{0} {5}        {1} seconds\n{0} {6}    {2}\n{0} {7}    {3}\n{0} {8} {4}",
+    " ",
+    style(seconds),
+    style(attempts),
+    style(func).bold(),
+    style(desc).dim(),
+    style("time:").fg(theme.base),
+    style("attempts:").fg(theme.base),
+    style("function:").fg(theme.base),
+    style("description:").fg(theme.base),
+  );
+  write_line!("\n{}", style("got it!").green().bold());
+}
+
+pub fn confirm(prompt: String) -> bool {
+  TERM.show_cursor().ok();
+
+  let theme = THEME.lock().unwrap();
+  let answer = Confirm::with_theme(&*theme)
+    .with_prompt(prompt)
+    .default(true)
+    .interact()
+    .unwrap_or(false);
+
+  TERM.hide_cursor().ok();
+  answer
+}
+
+pub fn print_share(grid: &str) {
+  clear_status();
+  write_line!("\n{}", grid);
+}
+
+/// copies text to the clipboard with an osc 52 escape, which works over ssh too
+pub fn copy(text: &str) {
+  use base64::prelude::*;
+
+  TERM
+    .write_str(&format!("\x1b]52;c;{}\x07", BASE64_STANDARD.encode(text)))
+    .ok();
+  TERM.flush().ok();
+}
+
+pub fn print_revealed(reveal: &RevealMessage) {
+  let theme = THEME.lock().unwrap();
+  clear_status();
+
+  write_line!(
+    "{0} {4}        {1}\n{0} {5}    {2}\n{0} {6} {3}",
+    " ",
+    style(&reveal.name).bold(),
+    style(&reveal.func).bold(),
+    style(&reveal.description).dim(),
+    style("name:").fg(theme.base),
+    style("function:").fg(theme.base),
+    style("description:").fg(theme.base),
+  );
+  write_line!("\n{}", style("better luck next time :c").red());
+}
+
+pub fn print_already_gave_up() {
+  clear_status();
+  write_line!("{}", style("you already gave up on today's nixdle").red());
+  write_line!("come back tomorrow for another try");
+}
+
 pub fn print_already_solved() {
   clear_status();
   write_line!(
@@ -151,6 +413,12 @@ pub fn print_warning(text: String) {
   clear_status();
   ewrite_line!("{}: {}", style("warning").yellow().bold(), text);
 }
+/// prints the config as toml, with where it's read from
+pub fn print_config(path: &std::path::Path, config: &str) {
+  write_line!("{}", style(format!("# {}", path.display())).dim());
+  write_line!("{}", config.trim_end());
+}
+
 pub fn print_error(text: String) {
   clear_status();
   ewrite_line!("{}: {}", style("error").red().bold(), text);
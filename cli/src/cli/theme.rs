@@ -2,7 +2,8 @@ use console::style;
 use dialoguer::theme::Theme as DialogTheme;
 use std::fmt;
 
-#[derive(clap::ValueEnum, Clone, Copy, Default)]
+#[derive(clap::ValueEnum, serde::Serialize, serde::Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
 pub enum Theme {
   #[default]
   Nix,
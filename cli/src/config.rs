@@ -0,0 +1,77 @@
+//! settings from the config file, so flags don't have to be repeated every run
+//! flags always win over the config file
+
+use std::{fs, path::PathBuf};
+
+use crate::cli::theme::Theme;
+use crate::error::Result;
+use crate::share::ShareFormat;
+
+/// everything that can be set in the config file, all optional
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+  /// api url to use
+  pub api: Option<String>,
+  pub theme: Option<Theme>,
+  pub hide_rules: Option<bool>,
+  /// where to keep the progress of unfinished games
+  pub lockfile_dir: Option<PathBuf>,
+  pub share_format: Option<ShareFormat>,
+  /// dataset directory for offline play
+  pub data: Option<PathBuf>,
+}
+
+impl Config {
+  pub fn path() -> PathBuf {
+    crate::paths::config_dir().join("config.toml")
+  }
+
+  /// reads the config file, an empty config if there's none
+  pub fn open() -> Result<Self> {
+    let path = Self::path();
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+
+    Self::parse(&fs::read_to_string(path)?)
+  }
+
+  fn parse(contents: &str) -> Result<Self> {
+    Ok(toml::from_str(contents)?)
+  }
+
+  pub fn to_toml(&self) -> String {
+    toml::to_string(self).expect("configs are always serializable")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_config() {
+    let config = Config::parse(
+      r#"
+        api = "http://0.0.0.0:8000"
+        theme = "lix"
+        hide-rules = true
+        share-format = "ascii"
+      "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.api.as_deref(), Some("http://0.0.0.0:8000"));
+    assert!(matches!(config.theme, Some(Theme::Lix)));
+    assert_eq!(config.hide_rules, Some(true));
+    assert!(matches!(config.share_format, Some(ShareFormat::Ascii)));
+    assert!(config.lockfile_dir.is_none());
+  }
+
+  #[test]
+  fn reject_unknown_settings() {
+    assert!(Config::parse("hide_rules = true").is_err());
+    assert!(Config::parse(r#"theme = "guix""#).is_err());
+  }
+}
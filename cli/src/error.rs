@@ -6,6 +6,20 @@ pub enum Error {
   Io(#[from] std::io::Error),
   #[error("json: {0}")]
   Json(#[from] serde_json::Error),
+  #[error("date: {0}")]
+  Date(#[from] chrono::ParseError),
+  #[error("config: {0}")]
+  Config(#[from] toml::de::Error),
+  #[error("a new nixdle is out, restart to play it")]
+  GameChanged,
+  #[error("{0}")]
+  Server(String),
+  #[error("no dataset to play offline with, pass one with --data")]
+  NoDataset,
+  #[error("that day isn't over yet")]
+  NotOver,
+  #[error("interrupted, see you later")]
+  Interrupted,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
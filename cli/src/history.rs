@@ -0,0 +1,159 @@
+use chrono::NaiveDate;
+use std::fs;
+
+use crate::error::Result;
+
+/// attempt counts past this one share the last histogram bar
+pub const HISTOGRAM_BARS: usize = 10;
+
+/// results of every daily game played
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct History {
+  /// oldest first, one per day
+  games: Vec<Record>,
+}
+
+/// result of a single day's game
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct Record {
+  pub date: NaiveDate,
+  /// valid attempts, including the winning one
+  pub attempts: usize,
+  /// time from the first prompt to the end
+  pub seconds: u64,
+  /// false if the player gave up
+  pub solved: bool,
+}
+
+pub struct Stats {
+  pub played: usize,
+  pub solved: usize,
+  /// consecutive days solved, up to today or yesterday
+  pub current_streak: usize,
+  pub max_streak: usize,
+  /// how many games were solved in 1, 2, ... attempts
+  pub histogram: [usize; HISTOGRAM_BARS],
+}
+
+impl History {
+  pub fn open() -> Result<Self> {
+    let path = crate::paths::data_dir().join("history.json");
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+  }
+
+  pub fn save(&self) -> Result<()> {
+    let dir = crate::paths::data_dir();
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("history.json"), serde_json::to_string(self)?)?;
+
+    Ok(())
+  }
+
+  /// records a day's result, replacing any earlier one for the same day
+  pub fn record(&mut self, record: Record) {
+    self.games.retain(|g| g.date != record.date);
+    self.games.push(record);
+    self.games.sort_by_key(|g| g.date);
+  }
+
+  /// `today` ends the current streak if the last game was before yesterday
+  pub fn stats(&self, today: NaiveDate) -> Stats {
+    let mut stats = Stats {
+      played: self.games.len(),
+      solved: 0,
+      current_streak: 0,
+      max_streak: 0,
+      histogram: [0; HISTOGRAM_BARS],
+    };
+
+    let mut last: Option<NaiveDate> = None;
+    for game in &self.games {
+      stats.current_streak = match game.solved {
+        // skipping a day breaks the streak too
+        true if last.and_then(|d| d.succ_opt()) == Some(game.date) => stats.current_streak + 1,
+        true => 1,
+        false => 0,
+      };
+      stats.max_streak = stats.max_streak.max(stats.current_streak);
+      last = Some(game.date);
+
+      if game.solved {
+        stats.solved += 1;
+        stats.histogram[game.attempts.clamp(1, HISTOGRAM_BARS) - 1] += 1;
+      }
+    }
+
+    // today's game might just not be played yet
+    if last.is_some_and(|last| last.succ_opt().is_some_and(|d| d < today)) {
+      stats.current_streak = 0;
+    }
+
+    stats
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn day(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 1, day).unwrap()
+  }
+
+  fn history(games: &[(u32, usize, bool)]) -> History {
+    let mut history = History::default();
+    for &(n, attempts, solved) in games {
+      history.record(Record {
+        date: day(n),
+        attempts,
+        seconds: 60,
+        solved,
+      });
+    }
+    history
+  }
+
+  #[test]
+  fn streaks() {
+    let stats = history(&[(1, 3, true), (2, 5, true), (3, 9, false), (4, 2, true)]).stats(day(5));
+    assert_eq!(stats.played, 4);
+    assert_eq!(stats.solved, 3);
+    assert_eq!(stats.current_streak, 1);
+    assert_eq!(stats.max_streak, 2);
+  }
+
+  #[test]
+  fn skipped_day_breaks_streak() {
+    let stats = history(&[(1, 3, true), (2, 5, true), (4, 2, true), (5, 1, true)]).stats(day(5));
+    assert_eq!(stats.current_streak, 2);
+    assert_eq!(stats.max_streak, 2);
+  }
+
+  #[test]
+  fn missed_days_end_the_current_streak() {
+    let history = history(&[(3, 3, true), (4, 2, true)]);
+    assert_eq!(history.stats(day(4)).current_streak, 2);
+    assert_eq!(history.stats(day(5)).current_streak, 2);
+    assert_eq!(history.stats(day(6)).current_streak, 0);
+    assert_eq!(history.stats(day(31)).max_streak, 2);
+  }
+
+  #[test]
+  fn histogram_buckets() {
+    let stats = history(&[(3, 1, true), (1, 1, true), (2, 25, true), (4, 4, false)]).stats(day(5));
+    assert_eq!(stats.histogram[0], 2);
+    assert_eq!(stats.histogram[HISTOGRAM_BARS - 1], 1);
+    assert_eq!(stats.histogram.iter().sum::<usize>(), 3);
+  }
+
+  #[test]
+  fn record_replaces_same_day() {
+    let history = history(&[(1, 3, false), (1, 4, true)]);
+    assert_eq!(history.games.len(), 1);
+    assert!(history.games[0].solved);
+  }
+}
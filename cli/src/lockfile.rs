@@ -1,54 +1,454 @@
-use std::{fs, path::Path};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use nixdle::api::AttemptMessage;
+use std::{
+  fs, io,
+  path::{Path, PathBuf},
+};
 
 use crate::crypto::verify_hmac;
 use crate::error::Result;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// version of the lockfile format, bumped on every incompatible change
+/// lockfiles without one are from before it was kept track of
+const SCHEMA: u64 = 2;
+
+/// lockfiles of games older than this many days are pruned
+const KEEP_DAYS: u64 = 7;
+
+/// the single lockfile kept before there was one per server & day
+const LEGACY_PATH: &str = "/tmp/nixdle.lock";
+const LEGACY_SIGNATURE_PATH: &str = "/tmp/nixdle.lock.sig";
+
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Lockfile {
+  pub schema: u64,
   pub date: String,
   pub success: bool,
-  pub attempts: usize,
-  pub attempted: Vec<String>,
+  /// every valid attempt, in order
+  pub attempts: Vec<Attempt>,
   pub version: String,
+  /// session token issued by the server
+  pub session: String,
+  /// the answer was revealed without solving
+  pub gave_up: bool,
+  /// when the current sitting started, None between sittings or if it's not known
+  pub started: Option<DateTime<Utc>>,
+  /// seconds spent in earlier sittings, so breaks don't count as time taken
+  #[serde(default)]
+  pub seconds_before: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Attempt {
+  pub guess: String,
+  /// what the server said about it, None if it's not known
+  pub feedback: Option<AttemptMessage>,
+  pub at: Option<DateTime<Utc>>,
+}
+
+/// the lockfile before there was a schema
+#[derive(serde::Deserialize)]
+struct Legacy {
+  date: String,
+  success: bool,
+  attempted: Vec<String>,
+  version: String,
+  #[serde(default)]
+  session: String,
+  /// only kept by later versions
+  #[serde(default)]
+  feedback: Vec<AttemptMessage>,
+  #[serde(default)]
+  gave_up: bool,
+}
+
+/// where the lockfiles of a server are kept, `api` is None when playing offline
+/// `base` overrides the default of the xdg state directory
+pub fn dir(base: Option<&Path>, api: Option<&str>) -> PathBuf {
+  let base = base
+    .map(Path::to_path_buf)
+    .unwrap_or_else(crate::paths::state_dir);
+  base.join(host(api))
+}
+
+/// the lockfile of a day's game
+pub fn path(dir: &Path, date: &str) -> PathBuf {
+  dir.join(format!("{}.json", date))
+}
+
+/// session of the newest lockfile, to resume before knowing which day it is
+/// the lockfile isn't verified, the server only resumes sessions it knows anyway
+/// falls back to the legacy lockfile, which is only moved once the day is known
+pub fn last_session(dir: &Path) -> Option<String> {
+  let newest = fs::read_dir(dir)
+    .into_iter()
+    .flatten()
+    .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+    .filter(|name| {
+      name
+        .strip_suffix(".json")
+        .is_some_and(|date| date.parse::<NaiveDate>().is_ok())
+    })
+    .max()
+    .map(|name| dir.join(name))
+    .unwrap_or_else(|| LEGACY_PATH.into());
+
+  let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(newest).ok()?).ok()?;
+  value
+    .get("session")?
+    .as_str()
+    .filter(|session| !session.is_empty())
+    .map(str::to_string)
+}
+
+/// removes the lockfiles of games that are long over
+pub fn prune(dir: &Path, today: NaiveDate) -> io::Result<()> {
+  let oldest = today - Days::new(KEEP_DAYS);
+
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    let date = path
+      .file_name()
+      .and_then(|name| name.to_str())
+      .and_then(|name| name.split('.').next())
+      .and_then(|date| date.parse::<NaiveDate>().ok());
+
+    if date.is_some_and(|date| date < oldest) {
+      fs::remove_file(path)?;
+    }
+  }
+
+  Ok(())
+}
+
+/// name of a server's lockfile directory, its host & port
+fn host(api: Option<&str>) -> String {
+  let Some(api) = api else {
+    return "offline".to_string();
+  };
+
+  let host = match reqwest::Url::parse(api) {
+    Ok(url) => match (url.host_str(), url.port()) {
+      (Some(host), Some(port)) => format!("{}_{}", host, port),
+      (Some(host), None) => host.to_string(),
+      (None, _) => api.to_string(),
+    },
+    Err(_) => api.to_string(),
+  };
+
+  // nothing that could escape the directory
+  let host = host.replace(
+    |c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-',
+    "_",
+  );
+  match host.trim_matches('.').is_empty() {
+    true => "_".to_string(),
+    false => host,
+  }
+}
+
+/// the signature next to a lockfile
+fn signature_path(path: &Path) -> PathBuf {
+  path.with_extension("json.sig")
 }
 
 impl Lockfile {
-  pub fn open(key: &str) -> Result<Self> {
-    let path = Path::new(crate::LOCKFILE_PATH);
-    let signature_path = Path::new(crate::LOCKFILE_SIGNATURE_PATH);
+  /// opens the lockfile at `path`, moving the legacy one there if it's for the same game
+  pub fn open(path: &Path, key: &str) -> Result<Self> {
+    Self::open_or_migrate(
+      path,
+      (Path::new(LEGACY_PATH), Path::new(LEGACY_SIGNATURE_PATH)),
+      key,
+    )
+  }
 
+  fn open_or_migrate(path: &Path, legacy: (&Path, &Path), key: &str) -> Result<Self> {
     if path.exists() {
-      let contents = fs::read_to_string(path)?;
-      let signature = fs::read(signature_path)?;
+      return Ok(Self::read(path, &signature_path(path), key)?.unwrap_or_else(Lockfile::default));
+    }
 
-      if verify_hmac(key, &contents, &signature) {
-        return Ok(serde_json::from_str(&contents)?);
-      }
+    // the key is made from the day & the server's version, so it only verifies for the same game
+    if legacy.0.exists()
+      && let Some(lockfile) = Self::read(legacy.0, legacy.1, key)?
+    {
+      lockfile.save(path, key)?;
+      fs::remove_file(legacy.0)?;
+      fs::remove_file(legacy.1)?;
+      return Ok(lockfile);
     }
 
     Ok(Lockfile::default())
   }
 
-  pub fn save(&self, key: &str) -> Result<()> {
-    let path = Path::new(crate::LOCKFILE_PATH);
-    let signature_path = Path::new(crate::LOCKFILE_SIGNATURE_PATH);
+  /// reads a lockfile, None if its signature doesn't match
+  fn read(path: &Path, signature_path: &Path, key: &str) -> Result<Option<Self>> {
+    let contents = fs::read_to_string(path)?;
+    let signature = fs::read(signature_path)?;
+
+    match verify_hmac(key, &contents, &signature) {
+      true => Lockfile::parse(&contents).map(Some),
+      false => Ok(None),
+    }
+  }
+
+  pub fn save(&self, path: &Path, key: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)?;
+    }
 
     let contents = serde_json::to_string(self)?;
     let signature = crate::crypto::generate_hmac(key, &contents);
 
     fs::write(path, contents)?;
-    fs::write(signature_path, signature)?;
+    fs::write(signature_path(path), signature)?;
 
     Ok(())
   }
 
-  fn default() -> Self {
+  /// reads a lockfile of any schema, migrating it to the current one
+  fn parse(contents: &str) -> Result<Self> {
+    let value: serde_json::Value = serde_json::from_str(contents)?;
+
+    match value.get("schema").and_then(serde_json::Value::as_u64) {
+      None => Ok(serde_json::from_value::<Legacy>(value)?.into()),
+      Some(SCHEMA) => Ok(serde_json::from_value(value)?),
+      // written by a newer nixdle, nothing to do but start over
+      Some(_) => Ok(Lockfile::default()),
+    }
+  }
+
+  pub fn default() -> Self {
     Lockfile {
+      schema: SCHEMA,
       date: String::new(),
       success: false,
-      attempts: 0,
-      attempted: Vec::new(),
+      attempts: Vec::new(),
       version: String::new(),
+      session: String::new(),
+      gave_up: false,
+      started: None,
+      seconds_before: 0,
+    }
+  }
+
+  /// records a valid attempt
+  pub fn push(&mut self, feedback: AttemptMessage) {
+    self.success = feedback.success;
+    self.attempts.push(Attempt {
+      guess: feedback.guess.clone(),
+      feedback: Some(feedback),
+      at: Some(Utc::now()),
+    });
+  }
+
+  /// what the server said about every attempt it's known for
+  pub fn feedback(&self) -> impl DoubleEndedIterator<Item = &AttemptMessage> {
+    self.attempts.iter().filter_map(|a| a.feedback.as_ref())
+  }
+
+  /// starts timing a new sitting
+  /// a sitting that wasn't ended cleanly counts up to its last attempt
+  pub fn resume(&mut self) {
+    if let Some(started) = self.started {
+      let last = self.attempts.last().and_then(|a| a.at);
+      self.seconds_before += last.map_or(0, |last| seconds_between(started, last));
+    }
+    self.started = Some(Utc::now());
+  }
+
+  /// stops timing until the next sitting
+  pub fn pause(&mut self) {
+    self.seconds_before = self.seconds();
+    self.started = None;
+  }
+
+  /// time spent playing, over every sitting up to now
+  pub fn seconds(&self) -> u64 {
+    self.seconds_before
+      + self
+        .started
+        .map_or(0, |started| seconds_between(started, Utc::now()))
+  }
+}
+
+fn seconds_between(start: DateTime<Utc>, end: DateTime<Utc>) -> u64 {
+  (end - start).num_seconds().max(0) as u64
+}
+
+impl From<Legacy> for Lockfile {
+  fn from(legacy: Legacy) -> Self {
+    // feedback was kept for every attempt or none at all
+    let mut feedback = legacy.feedback.into_iter();
+    let attempts = legacy
+      .attempted
+      .into_iter()
+      .map(|guess| Attempt {
+        guess,
+        feedback: feedback.next(),
+        at: None,
+      })
+      .collect();
+
+    Lockfile {
+      schema: SCHEMA,
+      date: legacy.date,
+      success: legacy.success,
+      attempts,
+      version: legacy.version,
+      session: legacy.session,
+      gave_up: legacy.gave_up,
+      started: None,
+      seconds_before: 0,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn migrate_legacy() {
+    let legacy = r#"{
+      "date": "2025-01-01",
+      "success": false,
+      "attempts": 2,
+      "attempted": ["lib.flip", "builtins.substring"],
+      "version": "0.0.1",
+      "session": "abc"
+    }"#;
+
+    let lockfile = Lockfile::parse(legacy).unwrap();
+    assert_eq!(lockfile.schema, SCHEMA);
+    assert_eq!(lockfile.session, "abc");
+    assert_eq!(lockfile.attempts.len(), 2);
+    assert_eq!(lockfile.attempts[1].guess, "builtins.substring");
+    assert!(lockfile.attempts.iter().all(|a| a.feedback.is_none()));
+  }
+
+  #[test]
+  fn roundtrip() {
+    let mut lockfile = Lockfile::default();
+    lockfile.date = "2025-01-01".to_string();
+    lockfile.attempts.push(Attempt {
+      guess: "lib.flip".to_string(),
+      feedback: None,
+      at: Some(Utc::now()),
+    });
+
+    let parsed = Lockfile::parse(&serde_json::to_string(&lockfile).unwrap()).unwrap();
+    assert_eq!(parsed.date, "2025-01-01");
+    assert_eq!(parsed.attempts[0].guess, "lib.flip");
+    assert!(parsed.attempts[0].at.is_some());
+  }
+
+  #[test]
+  fn host_names() {
+    assert_eq!(host(None), "offline");
+    assert_eq!(
+      host(Some("https://adamperkowski.dev/api/nixdle")),
+      "adamperkowski.dev"
+    );
+    assert_eq!(host(Some("http://0.0.0.0:8000")), "0.0.0.0_8000");
+    assert_eq!(host(Some("../../etc")), ".._.._etc");
+    assert_eq!(host(Some("..")), "_");
+  }
+
+  #[test]
+  fn prune_old_lockfiles() {
+    let dir = std::env::temp_dir().join(format!("nixdle-prune-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for name in [
+      "2025-01-01.json",
+      "2025-01-01.json.sig",
+      "2025-01-20.json",
+      "notes.txt",
+    ] {
+      fs::write(dir.join(name), "").unwrap();
     }
+
+    prune(&dir, "2025-01-21".parse().unwrap()).unwrap();
+    let mut left: Vec<_> = fs::read_dir(&dir)
+      .unwrap()
+      .map(|e| e.unwrap().file_name().into_string().unwrap())
+      .collect();
+    left.sort();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(left, ["2025-01-20.json", "notes.txt"]);
+  }
+
+  #[test]
+  fn newer_schema_starts_over() {
+    let newer = format!(r#"{{"schema": {}, "date": "2025-01-01"}}"#, SCHEMA + 1);
+    assert!(Lockfile::parse(&newer).unwrap().date.is_empty());
+  }
+
+  #[test]
+  fn last_session_is_from_the_newest_lockfile() {
+    let dir = std::env::temp_dir().join(format!("nixdle-session-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    fs::write(dir.join("2025-01-20.json"), r#"{"session": "new"}"#).unwrap();
+    fs::write(dir.join("2025-01-19.json"), r#"{"session": "old"}"#).unwrap();
+    fs::write(dir.join("2025-01-20.json.sig"), "").unwrap();
+    let session = last_session(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(session.as_deref(), Some("new"));
+  }
+
+  #[test]
+  fn breaks_dont_count() {
+    let mut lockfile = Lockfile::default();
+    lockfile.started = Some(Utc::now() - chrono::Duration::hours(20));
+    lockfile.attempts.push(Attempt {
+      guess: "lib.flip".to_string(),
+      feedback: None,
+      at: Some(Utc::now() - chrono::Duration::hours(20) + chrono::Duration::seconds(90)),
+    });
+
+    // the last sitting ended without a pause, so it counts up to its last attempt
+    lockfile.resume();
+    assert_eq!(lockfile.seconds_before, 90);
+    assert!(lockfile.seconds() < 95);
+
+    lockfile.pause();
+    assert!(lockfile.started.is_none());
+    let paused = lockfile.seconds();
+    assert_eq!(lockfile.seconds(), paused);
+  }
+
+  #[test]
+  fn migrate_legacy_path() {
+    let dir = std::env::temp_dir().join(format!("nixdle-migrate-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let legacy = (dir.join("nixdle.lock"), dir.join("nixdle.lock.sig"));
+    let contents = r#"{
+      "date": "2025-01-01",
+      "success": true,
+      "attempted": ["lib.flip"],
+      "version": "0.0.1",
+      "session": "abc"
+    }"#;
+    fs::write(&legacy.0, contents).unwrap();
+    fs::write(&legacy.1, crate::crypto::generate_hmac("key", contents)).unwrap();
+    let path = dir.join("server").join("2025-01-01.json");
+
+    // another day's key leaves it be
+    let other = Lockfile::open_or_migrate(&path, (&legacy.0, &legacy.1), "other key").unwrap();
+    assert!(other.date.is_empty());
+    assert!(legacy.0.exists());
+
+    let migrated = Lockfile::open_or_migrate(&path, (&legacy.0, &legacy.1), "key").unwrap();
+    let reopened = Lockfile::open_or_migrate(&path, (&legacy.0, &legacy.1), "key").unwrap();
+    let legacy_left = legacy.0.exists() || legacy.1.exists();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(migrated.success);
+    assert_eq!(migrated.attempts[0].guess, "lib.flip");
+    assert!(reopened.success);
+    assert_eq!(reopened.session, "abc");
+    assert!(!legacy_left);
   }
 }
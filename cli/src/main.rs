@@ -1,25 +1,44 @@
+use dialoguer::History as _;
 use nixdle::api;
-use reqwest::Client;
 
+mod backend;
 mod cli;
+mod config;
 mod crypto;
 mod error;
+mod history;
 mod lockfile;
+mod paths;
+mod prompt;
+mod share;
 
-use error::Result;
+use backend::{Backend, Mode};
+use cli::args::{Command, ConfigCommand};
+use config::Config;
+use error::{Error, Result};
+use history::{History, Record};
 use lockfile::Lockfile;
 
 const DEFAULT_API_URL: &str = "https://adamperkowski.dev/api/nixdle";
-const LOCKFILE_PATH: &str = "/tmp/nixdle.lock";
-const LOCKFILE_SIGNATURE_PATH: &str = "/tmp/nixdle.lock.sig";
 
 #[tokio::main]
 async fn main() {
-  let code = if let Err(e) = run().await {
-    cli::print_error(e.to_string());
-    1
-  } else {
-    0
+  // the prompt reads ^C as a key, this catches it everywhere else (e.g. while waiting on the server)
+  let result = tokio::select! {
+    result = run() => result,
+    _ = tokio::signal::ctrl_c() => Err(Error::Interrupted),
+  };
+
+  let code = match result {
+    Ok(()) => 0,
+    Err(Error::Interrupted) => {
+      cli::print_error(Error::Interrupted.to_string());
+      130
+    }
+    Err(e) => {
+      cli::print_error(e.to_string());
+      1
+    }
   };
 
   cli::reset();
@@ -27,100 +46,400 @@ async fn main() {
 }
 
 async fn run() -> Result<()> {
-  let args = cli::args::parse();
-  let url = args.api;
+  let args = cli::args::parse()?;
 
-  cli::print_welcome();
-  cli::print_status(format!("connecting to {}", url));
+  let mode = match (&args.command, args.date) {
+    (Some(Command::Stats), _) => {
+      cli::print_stats(&History::open()?.stats(chrono::Local::now().date_naive()));
+      return Ok(());
+    }
+    (
+      Some(Command::Config {
+        command: ConfigCommand::Show,
+      }),
+      _,
+    ) => {
+      cli::print_config(&Config::path(), &args.effective().to_toml());
+      return Ok(());
+    }
+    (Some(Command::Practice), _) => Mode::Practice,
+    (None, Some(date)) => Mode::Archive(date),
+    (None, None) => Mode::Daily,
+  };
 
-  let client = Client::new();
-  let start_message = request_start(&client, &format!("{}/start", url)).await?;
+  cli::print_welcome(match mode {
+    Mode::Daily => "today's nix function".to_string(),
+    Mode::Archive(date) => format!("the nix function from {}", date),
+    Mode::Practice => "as many nix functions as you like".to_string(),
+  });
 
-  if start_message.version != env!("CARGO_PKG_VERSION") {
-    cli::print_warning(format!(
-      "version mismatch (server: {}, client: {})",
-      start_message.version,
-      env!("CARGO_PKG_VERSION")
-    ));
+  let mut backend = if args.offline {
+    let data_dir = args.data().ok_or(Error::NoDataset)?;
+    cli::print_status(format!("loading dataset from {}", data_dir.display()));
+    Backend::local(&data_dir, &mode)?
+  } else {
+    cli::print_status(format!("connecting to {}", args.api()));
+    Backend::remote(args.api())
+  };
+
+  match mode {
+    Mode::Practice => practice(&args, &mut backend).await,
+    _ => daily(&args, &mut backend, &mode).await,
   }
+}
 
-  let key = start_message.date.clone() + &start_message.version + &start_message.nix_commit;
-  let mut lockfile = Lockfile::open(&key)?;
+/// plays the daily game (or an archived one, which isn't saved)
+async fn daily(args: &cli::args::Cli, backend: &mut Backend, mode: &Mode) -> Result<()> {
+  let persist = matches!(mode, Mode::Daily);
+  let lockfile_dir = lockfile::dir(
+    args.lockfile_dir.as_deref(),
+    (!args.offline).then_some(args.api()),
+  );
+  // the server only resumes the session if it's for today's game
+  let saved_session = persist
+    .then(|| lockfile::last_session(&lockfile_dir))
+    .flatten();
+  let start_message = backend.start(mode, saved_session.as_deref()).await?;
+  check_version(&start_message);
+  if saved_session.as_ref() == Some(&start_message.session) {
+    cli::print_status("resuming session".to_string());
+  }
+
+  let mut key = start_message.date.clone() + &start_message.version + &start_message.nix_commit;
+  if args.offline {
+    // offline games pick a different function than the server's
+    key += "offline";
+  }
+  let lockfile_path = lockfile::path(&lockfile_dir, &start_message.date);
+  let mut lockfile = match persist {
+    true => Lockfile::open(&lockfile_path, &key)?,
+    false => Lockfile::default(),
+  };
+  let save = |lockfile: &Lockfile| match persist {
+    true => lockfile.save(&lockfile_path, &key),
+    false => Ok(()),
+  };
+  if persist {
+    let today = start_message.date.parse()?;
+    // best effort, a stale lockfile doesn't hurt anyone
+    lockfile::prune(&lockfile_dir, today).ok();
+  }
 
   if lockfile.success {
+    // the lockfile's signature only keeps out accidents, anyone can forge it
+    // so wins are checked with the server that signed them
+    // an unverified win still ends the day, its result just says it's unverified
+    let vouched = args.offline
+      || match verified(backend, &lockfile).await {
+        Ok(true) => true,
+        Ok(false) => {
+          cli::print_error("the server doesn't vouch for this win".to_string());
+          false
+        }
+        Err(err) => {
+          cli::print_error(format!("couldn't verify your win: {}", err));
+          false
+        }
+      };
+
     cli::print_already_solved();
+    share(args, mode, &lockfile, vouched);
+    return Ok(());
+  }
+  if lockfile.gave_up {
+    cli::print_already_gave_up();
+    share(args, mode, &lockfile, true);
     return Ok(());
   }
 
   if !args.hide_rules {
-    cli::print_rules(start_message.rules);
+    cli::print_rules(start_message.rules.clone());
+  }
+  if !lockfile.attempts.is_empty() {
+    cli::print_resumed(&lockfile.attempts);
+  }
+
+  lockfile.date = start_message.date.clone();
+  lockfile.version = start_message.version.clone();
+  lockfile.session = start_message.session.clone();
+  lockfile.resume();
+  save(&lockfile)?;
+
+  let completer = completer(backend).await;
+  let outcome = play(
+    args,
+    backend,
+    mode,
+    &start_message,
+    &completer,
+    &mut lockfile,
+    save,
+  )
+  .await?;
+  let seconds = lockfile.seconds();
+
+  match outcome {
+    Outcome::Quit => return Ok(()),
+    Outcome::Solved(msg) => cli::print_solved(
+      &msg.func.unwrap_or_default(),
+      &msg.description.unwrap_or_default(),
+      lockfile.attempts.len(),
+      seconds,
+      &lockfile.date,
+      matches!(mode, Mode::Archive(_)),
+    ),
+    Outcome::GaveUp(reveal) => cli::print_revealed(&reveal),
+  }
+
+  if persist {
+    let mut history = History::open()?;
+    history.record(Record {
+      date: lockfile.date.parse()?,
+      attempts: lockfile.attempts.len(),
+      seconds,
+      solved: lockfile.success,
+    });
+    history.save()?;
   }
 
-  lockfile.date = start_message.date;
-  lockfile.version = start_message.version;
-  lockfile.save(&key)?;
+  share(args, mode, &lockfile, true);
+  Ok(())
+}
 
-  let attempt_url = &start_message.attempt_url;
-  let time_started = std::time::Instant::now();
+/// plays random games until the player has had enough, without saving anything
+async fn practice(args: &cli::args::Cli, backend: &mut Backend) -> Result<()> {
+  let mut rules = !args.hide_rules;
+  let completer = completer(backend).await;
 
   loop {
-    let input = cli::dialog(format!("guess#{}", lockfile.attempts));
+    let start_message = backend.start(&Mode::Practice, None).await?;
+    check_version(&start_message);
+
+    if std::mem::take(&mut rules) {
+      cli::print_rules(start_message.rules.clone());
+    }
+
+    let mut lockfile = Lockfile::default();
+    lockfile.date = start_message.date.clone();
+    lockfile.session = start_message.session.clone();
+    lockfile.started = Some(chrono::Utc::now());
 
-    cli::print_status(format!("sending to {}", attempt_url));
+    match play(
+      args,
+      backend,
+      &Mode::Practice,
+      &start_message,
+      &completer,
+      &mut lockfile,
+      |_| Ok(()),
+    )
+    .await?
+    {
+      Outcome::Quit => return Ok(()),
+      Outcome::Solved(msg) => cli::print_practice_solved(
+        &msg.func.unwrap_or_default(),
+        &msg.description.unwrap_or_default(),
+        lockfile.attempts.len(),
+        lockfile.seconds(),
+      ),
+      Outcome::GaveUp(reveal) => cli::print_revealed(&reveal),
+    }
+
+    if !cli::confirm("next one".to_string()) {
+      return Ok(());
+    }
+  }
+}
+
+/// checks the win in a lockfile with the server
+/// the token only has to be for the same day & session, the server's count of attempts can
+/// differ from the lockfile's (e.g. after a server restart or an attempt cut off by ctrl-c)
+async fn verified(backend: &Backend, lockfile: &Lockfile) -> Result<bool> {
+  let token = lockfile
+    .feedback()
+    .find(|m| m.success)
+    .and_then(|m| m.token.as_deref());
+  let Some(token) = token else {
+    return Ok(false);
+  };
+
+  Ok(
+    backend
+      .verify(token)
+      .await?
+      .is_some_and(|c| c.date == lockfile.date && c.session == lockfile.session),
+  )
+}
+
+/// completion for the guess prompt
+/// servers from before completion don't know the paths, so there's just none then
+async fn completer(backend: &Backend) -> prompt::Completer {
+  prompt::Completer::new(backend.paths().await.unwrap_or_default())
+}
+
+/// how a round ended
+enum Outcome {
+  /// with the winning attempt
+  Solved(api::AttemptMessage),
+  GaveUp(api::RevealMessage),
+  /// left without finishing, to come back later
+  Quit,
+}
+
+/// prompts for guesses (or commands) until one is right or the player gives up,
+/// keeping track of them in the lockfile
+async fn play(
+  args: &cli::args::Cli,
+  backend: &Backend,
+  mode: &Mode,
+  start_message: &api::StartMessage,
+  completer: &prompt::Completer,
+  lockfile: &mut Lockfile,
+  save: impl Fn(&Lockfile) -> Result<()>,
+) -> Result<Outcome> {
+  let mut suggestion = None;
+  let mut guesses = dialoguer::BasicHistory::new().no_duplicates(true);
+  for attempt in &lockfile.attempts {
+    guesses.write(&attempt.guess);
+  }
+
+  loop {
+    let input = match cli::dialog(
+      format!("guess#{}", lockfile.attempts.len()),
+      suggestion.take(),
+      completer,
+      &mut guesses,
+    ) {
+      Ok(input) => input,
+      // ^C at the prompt is the same as :quit
+      Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+        lockfile.pause();
+        save(lockfile)?;
+        return Ok(Outcome::Quit);
+      }
+      Err(e) => return Err(e.into()),
+    };
+
+    match prompt::Command::parse(&input) {
+      None => {}
+      Some(Err(command)) => {
+        cli::print_error(format!("there's no {} command, try :help", command));
+        continue;
+      }
+      Some(Ok(prompt::Command::Help)) => {
+        cli::print_commands(prompt::COMMANDS);
+        continue;
+      }
+      Some(Ok(prompt::Command::Rules)) => {
+        cli::print_rules(start_message.rules.clone());
+        continue;
+      }
+      Some(Ok(prompt::Command::History)) => {
+        cli::print_history(&lockfile.attempts);
+        continue;
+      }
+      Some(Ok(prompt::Command::Hint)) => {
+        let clues = lockfile
+          .feedback()
+          .next_back()
+          .map(|m| m.clues.clone())
+          .unwrap_or_default();
+        let attempts = lockfile.attempts.len();
+        let next_in = (clues.len() < start_message.possible_clues as usize)
+          .then(|| nixdle::attempts_until_next_clue(clues.len(), attempts));
+        cli::print_hint(&clues, next_in);
+        continue;
+      }
+      Some(Ok(prompt::Command::Share)) => {
+        share(args, mode, lockfile, true);
+        continue;
+      }
+      Some(Ok(prompt::Command::Quit)) => {
+        lockfile.pause();
+        save(lockfile)?;
+        return Ok(Outcome::Quit);
+      }
+      Some(Ok(prompt::Command::GiveUp)) => {
+        if !cli::confirm("really give up".to_string()) {
+          continue;
+        }
+
+        let data = api::GiveUpData {
+          session: lockfile.session.clone(),
+          date: lockfile.date.clone(),
+        };
+        let reveal = backend.give_up(&data).await?;
+        lockfile.gave_up = true;
+        save(lockfile)?;
+        return Ok(Outcome::GaveUp(reveal));
+      }
+    }
+
+    cli::print_status(backend.sending());
 
     let attempt_data = api::AttemptData {
       input: input.clone(),
-      attempts: lockfile.attempts,
+      session: lockfile.session.clone(),
+      date: lockfile.date.clone(),
     };
-    let attempt_message = request_attempt(&client, attempt_url, &attempt_data).await?;
-
-    if let Some(msg) = attempt_message {
-      cli::print_status("saving".to_string());
-
-      lockfile.attempts += 1;
-      lockfile.attempted.push(input);
-
-      if msg.success {
-        cli::print_solved(
-          &msg.func.unwrap_or_default(),
-          &msg.description.unwrap_or_default(),
-          lockfile.attempts,
-          time_started.elapsed().as_secs(),
-          &lockfile.date,
-        );
-
-        lockfile.success = true;
-        lockfile.save(&key)?;
-        break;
-      } else {
-        cli::print_attempt(msg.clues, msg.args.to_string(), msg.input, msg.output);
-      }
 
-      lockfile.save(&key)?;
-    } else {
-      cli::print_error("the server doesn't know this one :c".to_string());
-      continue;
+    match backend
+      .attempt(&attempt_data, lockfile.attempts.len())
+      .await?
+    {
+      api::AttemptResponse::Attempt(msg) => {
+        cli::print_status("saving".to_string());
+
+        lockfile.push(msg.clone());
+
+        if msg.success {
+          save(lockfile)?;
+          return Ok(Outcome::Solved(msg));
+        }
+
+        cli::print_attempt(msg.clues, msg.args.to_string(), &msg.inputs, msg.output);
+        save(lockfile)?;
+      }
+      api::AttemptResponse::Unknown(suggestions) => {
+        cli::print_error("never heard of this one :c".to_string());
+        if !suggestions.is_empty() {
+          cli::print_suggestions(&suggestions);
+          suggestion = suggestions.into_iter().next();
+        }
+      }
+      api::AttemptResponse::Ambiguous(paths) => {
+        cli::print_error("that name is used in more than one place :o".to_string());
+        cli::print_ambiguous(&paths);
+        suggestion = paths.into_iter().next();
+      }
     }
   }
-
-  Ok(())
 }
 
-async fn request_start(client: &Client, url: &str) -> Result<api::StartMessage> {
-  let res = client.get(url).send().await?;
-
-  res.json::<api::StartMessage>().await.map_err(Into::into)
+fn check_version(start_message: &api::StartMessage) {
+  if start_message.version != env!("CARGO_PKG_VERSION") {
+    cli::print_warning(format!(
+      "version mismatch (server: {}, client: {})",
+      start_message.version,
+      env!("CARGO_PKG_VERSION")
+    ));
+  }
 }
 
-async fn request_attempt(
-  client: &Client,
-  url: &str,
-  data: &api::AttemptData,
-) -> Result<Option<api::AttemptMessage>> {
-  let res = client.post(url).json(data).send().await?;
-
-  res
-    .json::<Option<api::AttemptMessage>>()
-    .await
-    .map_err(Into::into)
+/// prints the result grid & copies it if asked to
+fn share(args: &cli::args::Cli, mode: &Mode, lockfile: &Lockfile, vouched: bool) {
+  let grid = share::grid(
+    mode,
+    &lockfile.date,
+    &lockfile.attempts,
+    lockfile.gave_up,
+    vouched,
+    args.share_format(),
+  );
+  cli::print_share(&grid);
+
+  if args.copy {
+    cli::copy(&grid);
+    cli::print_status("copied to clipboard".to_string());
+  }
 }
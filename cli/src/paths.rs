@@ -0,0 +1,34 @@
+//! where files are kept, following the xdg base directory spec
+
+use std::{env, path::PathBuf};
+
+/// directory for settings (e.g. the config file)
+pub fn config_dir() -> PathBuf {
+  xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// directory for persistent data (e.g. the game history)
+pub fn data_dir() -> PathBuf {
+  xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// directory for state that's fine to lose (e.g. lockfiles of games being played)
+pub fn state_dir() -> PathBuf {
+  xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+/// resolves an xdg base directory, falling back to its default under the home directory
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+  let base = env::var_os(var)
+    .map(PathBuf::from)
+    // the spec says relative paths are invalid and should be ignored
+    .filter(|p| p.is_absolute())
+    .unwrap_or_else(|| {
+      env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(fallback)
+    });
+
+  base.join("nixdle")
+}
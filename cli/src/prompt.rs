@@ -0,0 +1,174 @@
+//! commands typed at the guess prompt instead of a guess, & completion of guesses
+
+use nixdle::search;
+
+pub enum Command {
+  Help,
+  Rules,
+  History,
+  Hint,
+  GiveUp,
+  Share,
+  Quit,
+}
+
+/// every command with what it does, for :help
+pub const COMMANDS: &[(&str, &str)] = &[
+  (":help", "show this"),
+  (":rules", "show the rules"),
+  (":history", "show your guesses so far"),
+  (
+    ":hint",
+    "show the path clues you got & when the next one comes",
+  ),
+  (":giveup", "reveal the answer (ends the game)"),
+  (":share", "show the result grid so far"),
+  (":quit", "leave, you can come back later"),
+];
+
+impl Command {
+  /// parses a command, returns None if the input is a guess
+  /// & the input back as an error if it's not a known command
+  pub fn parse(input: &str) -> Option<Result<Self, String>> {
+    let input = input.trim();
+    let name = input.strip_prefix(':')?;
+
+    Some(match name.to_lowercase().as_str() {
+      "help" | "h" | "?" => Ok(Command::Help),
+      "rules" => Ok(Command::Rules),
+      "history" => Ok(Command::History),
+      "hint" => Ok(Command::Hint),
+      "giveup" => Ok(Command::GiveUp),
+      "share" => Ok(Command::Share),
+      "quit" | "q" | "exit" => Ok(Command::Quit),
+      _ => Err(input.to_string()),
+    })
+  }
+}
+
+/// tab completion of function paths & commands
+pub struct Completer {
+  paths: Vec<String>,
+}
+
+impl Completer {
+  pub fn new(paths: Vec<String>) -> Self {
+    Completer { paths }
+  }
+
+  /// extends the input to what every match has in common,
+  /// or swaps it for the full path of the closest function if that doesn't get any further
+  fn complete(&self, input: &str) -> Option<String> {
+    if input.starts_with(':') {
+      let names = COMMANDS.iter().map(|(name, _)| *name);
+      return longest_prefix(input, names).filter(|c| c != input);
+    }
+
+    // with a dot in the input whole paths are completed, otherwise only names
+    let targets = self.paths.iter().map(|path| match input.contains('.') {
+      true => path.as_str(),
+      false => path.rsplit('.').next().unwrap_or(path),
+    });
+    if let Some(prefix) = longest_prefix(input, targets).filter(|p| p.len() > input.len()) {
+      return Some(prefix);
+    }
+
+    search::rank(input, self.paths.iter().map(String::as_str), 1)
+      .first()
+      .filter(|path| **path != input)
+      .map(|path| path.to_string())
+  }
+}
+
+impl dialoguer::Completion for Completer {
+  fn get(&self, input: &str) -> Option<String> {
+    self.complete(input)
+  }
+}
+
+/// what all the candidates starting with the input have in common
+/// returns None if none of them start with it
+fn longest_prefix<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+  candidates
+    .filter(|c| c.starts_with(input))
+    .fold(None, |common: Option<&str>, c| {
+      Some(match common {
+        None => c,
+        Some(common) => {
+          let len = common
+            .char_indices()
+            .zip(c.chars())
+            .find(|((_, a), b)| a != b)
+            .map(|((i, _), _)| i)
+            .unwrap_or(common.len().min(c.len()));
+          &common[..len]
+        }
+      })
+    })
+    .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn guesses_are_not_commands() {
+    assert!(Command::parse("lib.flip").is_none());
+    assert!(Command::parse("flip").is_none());
+  }
+
+  #[test]
+  fn parse_commands() {
+    assert!(matches!(Command::parse(":help"), Some(Ok(Command::Help))));
+    assert!(matches!(
+      Command::parse(" :GiveUp "),
+      Some(Ok(Command::GiveUp))
+    ));
+    assert!(matches!(Command::parse(":q"), Some(Ok(Command::Quit))));
+    assert!(matches!(Command::parse(":flip"), Some(Err(c)) if c == ":flip"));
+  }
+
+  fn completer() -> Completer {
+    Completer::new(
+      [
+        "builtins.map",
+        "lib.attrsets.mapAttrs",
+        "lib.attrsets.mapAttrsRecursive",
+        "lib.attrsets.mapAttrsRecursiveCond",
+        "lib.strings.concatMapStrings",
+      ]
+      .map(str::to_string)
+      .to_vec(),
+    )
+  }
+
+  #[test]
+  fn complete_common_prefix() {
+    let completer = completer();
+    assert_eq!(
+      completer.complete("mapAttrsR").as_deref(),
+      Some("mapAttrsRecursive")
+    );
+    assert_eq!(
+      completer.complete("lib.attrsets.mapAttrsR").as_deref(),
+      Some("lib.attrsets.mapAttrsRecursive")
+    );
+    assert_eq!(completer.complete(":gi").as_deref(), Some(":giveup"));
+  }
+
+  #[test]
+  fn complete_full_path() {
+    let completer = completer();
+    assert_eq!(
+      completer.complete("mapAttrsRecursive").as_deref(),
+      Some("lib.attrsets.mapAttrsRecursive")
+    );
+    assert_eq!(
+      completer.complete("cms").as_deref(),
+      Some("lib.strings.concatMapStrings")
+    );
+    assert_eq!(completer.complete("lib.attrsets.mapAttrsRecursive"), None);
+    assert_eq!(completer.complete(""), None);
+  }
+}
@@ -0,0 +1,209 @@
+//! spoiler-free summaries of a game, safe to paste anywhere
+
+use nixdle::api::{Matches, TypeMatch};
+
+use crate::backend::Mode;
+use crate::lockfile::Attempt;
+
+#[derive(clap::ValueEnum, serde::Serialize, serde::Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareFormat {
+  #[default]
+  Emoji,
+  /// for places that mangle emoji
+  Ascii,
+}
+
+struct Symbols {
+  more: &'static str,
+  less: &'static str,
+  exact: &'static str,
+  compatible: &'static str,
+  wrong: &'static str,
+  clue: &'static str,
+}
+
+impl ShareFormat {
+  fn symbols(self) -> Symbols {
+    match self {
+      ShareFormat::Emoji => Symbols {
+        more: "⬆️",
+        less: "⬇️",
+        exact: "🟩",
+        compatible: "🟨",
+        wrong: "🟥",
+        clue: "💡",
+      },
+      ShareFormat::Ascii => Symbols {
+        more: "+",
+        less: "-",
+        exact: "#",
+        compatible: "~",
+        wrong: "x",
+        clue: "*",
+      },
+    }
+  }
+}
+
+/// one row per attempt: arity, input types, output type & clues seen
+/// attempts without feedback are counted but left out
+/// replays, practice games & wins the server doesn't vouch for are marked
+/// so they don't pass for a daily result
+pub fn grid(
+  mode: &Mode,
+  date: &str,
+  attempts: &[Attempt],
+  gave_up: bool,
+  vouched: bool,
+  format: ShareFormat,
+) -> String {
+  let symbols = format.symbols();
+  let type_match = |m: &TypeMatch| match m {
+    TypeMatch::Exact => symbols.exact,
+    TypeMatch::Compatible => symbols.compatible,
+    TypeMatch::Wrong => symbols.wrong,
+  };
+
+  let count = attempts.len();
+  let result = match attempts.last().and_then(|a| a.feedback.as_ref()) {
+    Some(msg) if msg.success => format!("solved in {}", count),
+    _ if gave_up => format!("gave up after {}", count),
+    _ => format!("{} attempts so far", count),
+  };
+  let game = match mode {
+    Mode::Daily => date.to_string(),
+    Mode::Archive(_) => format!("{} (replay)", date),
+    Mode::Practice => "practice".to_string(),
+  };
+  let unverified = match vouched {
+    true => "",
+    false => " (unverified)",
+  };
+  let mut lines = vec![format!("nixdle {}: {}{}", game, result, unverified)];
+
+  let mut clues_seen = 0;
+  for msg in attempts.iter().filter_map(|a| a.feedback.as_ref()) {
+    let args = match msg.args {
+      Matches::TooLow => symbols.more,
+      Matches::TooHigh => symbols.less,
+      Matches::JustRight => symbols.exact,
+    };
+    let inputs: String = msg.inputs.iter().map(type_match).collect();
+    // a win reveals the whole path, which says more than it should
+    if !msg.success {
+      clues_seen = msg.clues.len();
+    }
+    let clues = symbols.clue.repeat(clues_seen);
+
+    lines.push(
+      format!("{} {} {} {}", args, inputs, type_match(&msg.output), clues)
+        .trim_end()
+        .to_string(),
+    );
+  }
+
+  lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use nixdle::api::AttemptMessage;
+
+  fn msg(success: bool, args: Matches, inputs: &[TypeMatch], clues: usize) -> Attempt {
+    let feedback = AttemptMessage {
+      success,
+      guess: "lib.flip".to_string(),
+      func: None,
+      description: None,
+      clues: vec!["lib".to_string(); clues],
+      args,
+      inputs: inputs.to_vec(),
+      output: if success {
+        TypeMatch::Exact
+      } else {
+        TypeMatch::Wrong
+      },
+      token: None,
+    };
+    Attempt {
+      guess: feedback.guess.clone(),
+      feedback: Some(feedback),
+      at: None,
+    }
+  }
+
+  #[test]
+  fn ascii_grid_hides_the_answer() {
+    use TypeMatch::*;
+    let feedback = [
+      msg(false, Matches::TooLow, &[Exact, Wrong], 0),
+      msg(false, Matches::TooHigh, &[Compatible, Exact, Wrong], 1),
+      msg(true, Matches::JustRight, &[Exact, Exact], 3),
+    ];
+
+    assert_eq!(
+      grid(
+        &Mode::Daily,
+        "2025-01-01",
+        &feedback,
+        false,
+        true,
+        ShareFormat::Ascii
+      ),
+      "nixdle 2025-01-01: solved in 3\n+ #x x\n- ~#x x *\n# ## # *"
+    );
+  }
+
+  #[test]
+  fn grid_header() {
+    let feedback = [msg(false, Matches::TooLow, &[TypeMatch::Exact], 0)];
+
+    let header = |mode, gave_up| {
+      let grid = grid(
+        &mode,
+        "2025-01-01",
+        &feedback,
+        gave_up,
+        true,
+        ShareFormat::Emoji,
+      );
+      grid.lines().next().unwrap().to_string()
+    };
+    assert_eq!(
+      header(Mode::Daily, false),
+      "nixdle 2025-01-01: 1 attempts so far"
+    );
+    assert_eq!(
+      header(Mode::Daily, true),
+      "nixdle 2025-01-01: gave up after 1"
+    );
+    assert_eq!(
+      header(Mode::Archive("2025-01-01".parse().unwrap()), true),
+      "nixdle 2025-01-01 (replay): gave up after 1"
+    );
+    assert_eq!(
+      header(Mode::Practice, false),
+      "nixdle practice: 1 attempts so far"
+    );
+  }
+
+  #[test]
+  fn unverified_wins_say_so() {
+    let feedback = [msg(true, Matches::JustRight, &[TypeMatch::Exact], 0)];
+    let grid = grid(
+      &Mode::Daily,
+      "2025-01-01",
+      &feedback,
+      false,
+      false,
+      ShareFormat::Ascii,
+    );
+
+    assert_eq!(
+      grid.lines().next(),
+      Some("nixdle 2025-01-01: solved in 1 (unverified)")
+    );
+  }
+}
@@ -22,7 +22,7 @@
     {
       packages = forAllSystems (pkgs: rec {
         default = nixdle;
-        nixdle = pkgs.callPackage ./nix/packages/cli.nix { };
+        nixdle = pkgs.callPackage ./nix/packages/cli.nix { inherit data; };
         server = pkgs.callPackage ./nix/packages/server.nix { inherit data; };
         data = pkgs.callPackage ./nix/packages/data.nix {
           inherit (noogle.packages.${pkgs.stdenv.hostPlatform.system})
@@ -30,6 +30,10 @@
             pesto
             salt
             ;
+          nixpkgs-rev = noogle.inputs.nixpkgs-master.rev;
+          # the date of the pinned nixpkgs, so the dataset stays reproducible
+          nixpkgs-last-modified = noogle.inputs.nixpkgs-master.lastModified;
+          nix-version = pkgs.lib.fileContents "${noogle.inputs.nix-master}/.version";
         };
       });
 
@@ -0,0 +1,48 @@
+//! per-attempt cost on a real dataset
+//! uses `lib/data` (e.g. `nix build .#data -o lib/data`) or DATA_DIR
+
+use std::{env, fs, hint::black_box};
+
+use chrono::NaiveDate;
+use criterion::{BatchSize, Criterion};
+use nixdle::{State, parse_builtin_types, parse_functions_filtered};
+
+fn load() -> Option<State> {
+  let data_dir = env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+  let read = |name: &str| fs::read_to_string(format!("{}/{}", data_dir, name)).ok();
+
+  let builtin_types = parse_builtin_types(&read("builtin_types.json")?).ok()?;
+  let functions = parse_functions_filtered(&builtin_types, &read("functions.json")?).ok()?;
+  Some(State::new(functions, builtin_types, Default::default()))
+}
+
+fn main() {
+  let Some(mut state) = load() else {
+    eprintln!("no dataset found, set DATA_DIR or build one into lib/data");
+    return;
+  };
+  let mut c = Criterion::default().configure_from_args();
+
+  c.bench_function("index", |b| {
+    b.iter_batched(
+      || (state.functions.clone(), state.builtin_types.clone()),
+      |(f, t)| State::new(f, t, Default::default()),
+      BatchSize::LargeInput,
+    )
+  });
+
+  state.init_daily_game(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), "bench");
+
+  let mut group = c.benchmark_group("attempt");
+  for (name, input) in [
+    ("path", "lib.attrsets.mapAttrs"),
+    ("alias", "builtins.mapAttrs"),
+    ("name", "MAPATTRS"),
+    ("unknown", "mapAtrs"),
+  ] {
+    group.bench_function(name, |b| b.iter(|| state.attempt_game(black_box(input), 0)));
+  }
+  group.finish();
+
+  c.final_summary();
+}
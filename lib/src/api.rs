@@ -3,35 +3,118 @@ use serde::{Deserialize, Serialize};
 
 use crate::function::Type;
 
+/// what the server answers with a conflict when the daily game has moved on
+/// other conflicts have their own reasons, so clients tell rollovers apart by this
+pub const ROLLED_OVER: &str = "the game has rolled over";
+
+/// data from client to server on start
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct StartData {
+  /// session to resume, if any
+  pub session: Option<String>,
+}
+
 /// data from client to server on attempt
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct AttemptData {
   pub input: String,
-  pub attempts: usize,
+  /// session token issued on start
+  pub session: String,
+  /// date of the game the attempt is meant for
+  pub date: String,
+}
+
+/// data from client to server on giving up
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct GiveUpData {
+  /// session token issued on start
+  pub session: String,
+  /// date of the game being given up on
+  pub date: String,
 }
 
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct StartMessage {
   pub date: String,
+  /// session token to send with every attempt
+  pub session: String,
   pub attempt_url: String,
+  /// where to go to give up & see the answer
+  pub giveup_url: String,
   pub possible_clues: u8,
   pub rules: String,
   pub version: String,
   pub nix_commit: String,
+  pub nix_version: String,
+  pub data_generated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+#[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct AttemptMessage {
   pub success: bool,
+  /// full path of the guessed function, as it's spelled in nixpkgs
+  pub guess: String,
   pub func: Option<String>,
   pub description: Option<String>,
   pub clues: Vec<String>,
   pub args: Matches,
-  pub input: bool,
-  pub output: bool,
+  /// how each guessed argument type compares to the one in the same position
+  pub inputs: Vec<TypeMatch>,
+  pub output: TypeMatch,
+  /// proof of the win signed by the server, only on winning attempts at daily & archived games
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub token: Option<String>,
+}
+
+/// data from client to server on verify
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct VerifyData {
+  /// token from the winning attempt
+  pub token: String,
+}
+
+/// a daily game solved on its day, as vouched for by the server that signed its token
+/// it only proves someone solved the game in that many attempts in that session,
+/// nothing stops them from peeking at the answer in another one first
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Completion {
+  /// date of the game that was solved
+  pub date: String,
+  /// session the game was solved in
+  pub session: String,
+  /// valid attempts, including the winning one
+  pub attempts: usize,
+  pub solved_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// the answer, for those who gave up
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RevealMessage {
+  /// name of the function (e.g. "mapAttrs")
+  pub name: String,
+  /// full path of the function (e.g. "lib.attrsets.mapAttrs")
+  pub func: String,
+  pub description: String,
+}
+
+/// answer to an attempt
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum AttemptResponse {
+  /// the guess was judged
+  Attempt(AttemptMessage),
+  /// the guess isn't a known function, with the closest ones that are
+  /// doesn't count as an attempt
+  Unknown(Vec<String>),
+  /// the guess is a name used by more than one function, with their full paths
+  /// doesn't count as an attempt
+  Ambiguous(Vec<String>),
 }
 
 /// how a guess compares to the actual value
+#[derive(Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Matches {
   TooLow,
@@ -50,8 +133,22 @@ impl Matches {
     }
   }
 
-  pub fn check_types(guess: (&Type, &Type), actual: (&Type, &Type)) -> (bool, bool) {
-    (guess.0 == actual.0, guess.1 == actual.1)
+  /// compares argument types position by position & the output types
+  pub fn check_types(
+    guess: (&[Type], &Type),
+    actual: (&[Type], &Type),
+  ) -> (Vec<TypeMatch>, TypeMatch) {
+    let inputs = guess
+      .0
+      .iter()
+      .enumerate()
+      .map(|(i, g)| match actual.0.get(i) {
+        Some(a) => TypeMatch::check(g, a),
+        None => TypeMatch::Wrong,
+      })
+      .collect();
+
+    (inputs, TypeMatch::check(guess.1, actual.1))
   }
 }
 
@@ -64,3 +161,124 @@ impl std::fmt::Display for Matches {
     }
   }
 }
+
+/// how a guessed type compares to the actual one
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum TypeMatch {
+  /// same type
+  Exact,
+  /// same kind of type with different details (e.g. a list of something else)
+  Compatible,
+  Wrong,
+}
+
+impl TypeMatch {
+  /// whether the type is at least compatible
+  pub fn is_close(self) -> bool {
+    self != TypeMatch::Wrong
+  }
+
+  pub fn check(guess: &Type, actual: &Type) -> Self {
+    if guess == actual {
+      return TypeMatch::Exact;
+    }
+
+    match (guess, actual) {
+      (Type::Any | Type::Var(_), _) | (_, Type::Any | Type::Var(_)) => TypeMatch::Compatible,
+      (Type::List(g), Type::List(a)) => match TypeMatch::check(g, a) {
+        TypeMatch::Exact => TypeMatch::Exact,
+        _ => TypeMatch::Compatible,
+      },
+      (Type::Function(ga, gr), Type::Function(aa, ar)) => {
+        match (TypeMatch::check(ga, aa), TypeMatch::check(gr, ar)) {
+          (TypeMatch::Exact, TypeMatch::Exact) => TypeMatch::Exact,
+          _ => TypeMatch::Compatible,
+        }
+      }
+      (Type::Union(g), Type::Union(a))
+        if g.iter().all(|t| a.contains(t)) && a.iter().all(|t| g.contains(t)) =>
+      {
+        TypeMatch::Exact
+      }
+      (Type::Union(g), _) if g.iter().any(|t| TypeMatch::check(t, actual).is_close()) => {
+        TypeMatch::Compatible
+      }
+      (_, Type::Union(a)) if a.iter().any(|t| TypeMatch::check(guess, t).is_close()) => {
+        TypeMatch::Compatible
+      }
+      _ => TypeMatch::Wrong,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn list(ty: Type) -> Type {
+    Type::List(Box::new(ty))
+  }
+
+  fn func(arg: Type, ret: Type) -> Type {
+    Type::Function(Box::new(arg), Box::new(ret))
+  }
+
+  fn var(name: &str) -> Type {
+    Type::Var(name.to_string())
+  }
+
+  fn check(guess: Type, actual: Type) -> TypeMatch {
+    TypeMatch::check(&guess, &actual)
+  }
+
+  #[test]
+  fn lists() {
+    assert!(check(list(Type::Int), list(Type::Int)) == TypeMatch::Exact);
+    assert!(check(list(Type::Int), list(Type::String)) == TypeMatch::Compatible);
+    assert!(check(list(Type::Int), Type::Int) == TypeMatch::Wrong);
+  }
+
+  #[test]
+  fn unions() {
+    let maybe_string = Type::Union(vec![Type::String, Type::Null]);
+    assert!(
+      check(
+        Type::Union(vec![Type::Null, Type::String]),
+        maybe_string.clone()
+      ) == TypeMatch::Exact
+    );
+    assert!(check(Type::String, maybe_string.clone()) == TypeMatch::Compatible);
+    assert!(check(maybe_string.clone(), Type::Null) == TypeMatch::Compatible);
+    assert!(check(Type::Int, maybe_string) == TypeMatch::Wrong);
+  }
+
+  #[test]
+  fn functions() {
+    let f = func(Type::Int, Type::Bool);
+    assert!(check(f.clone(), f.clone()) == TypeMatch::Exact);
+    assert!(check(func(Type::Int, Type::String), f.clone()) == TypeMatch::Compatible);
+    assert!(check(f, Type::Bool) == TypeMatch::Wrong);
+  }
+
+  #[test]
+  fn any_and_vars() {
+    assert!(check(Type::Any, Type::Int) == TypeMatch::Compatible);
+    assert!(check(Type::Int, Type::Any) == TypeMatch::Compatible);
+    assert!(check(var("a"), Type::String) == TypeMatch::Compatible);
+    assert!(check(list(Type::String), list(var("a"))) == TypeMatch::Compatible);
+    assert!(check(var("a"), var("a")) == TypeMatch::Exact);
+    assert!(check(var("a"), var("b")) == TypeMatch::Compatible);
+  }
+
+  #[test]
+  fn extra_arguments_are_wrong() {
+    let (inputs, output) = Matches::check_types(
+      (&[Type::String, Type::String, Type::Int], &Type::Bool),
+      (&[Type::String, Type::Int], &Type::Bool),
+    );
+
+    assert!(inputs == [TypeMatch::Exact, TypeMatch::Wrong, TypeMatch::Wrong]);
+    assert!(output == TypeMatch::Exact);
+  }
+}
@@ -0,0 +1,258 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub mod signature;
+
+pub use signature::{ParseError, Signature, TypeExpr};
+
+/// represents a nix function
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Function {
+  /// metadata
+  pub meta: Meta,
+  pub content: Option<Content>,
+}
+
+impl Function {
+  /// gets the function description
+  pub fn get_description(&self) -> Option<&str> {
+    self.content.as_ref().and_then(|c| c.content.as_deref())
+  }
+
+  /// gets the number of arguments the function takes
+  pub fn get_args_count(&self) -> usize {
+    if let Some(primop) = &self.meta.primop_meta
+      && let Some(args) = &primop.args
+    {
+      return args.len();
+    }
+
+    self
+      .meta
+      .signature
+      .as_deref()
+      .and_then(|s| signature::parse(s).ok())
+      .map(|s| s.ty.arity())
+      .unwrap_or(0)
+  }
+
+  /// gets the function argument & output types
+  pub fn get_types(&self, builtin_types: &[(String, String)]) -> Option<(Vec<Type>, Type)> {
+    if let Some(signature) = &self.meta.signature {
+      return types_from_signature(signature);
+    }
+
+    if self.meta.path.len() == 2 && self.meta.path.first()? == "builtins" {
+      let name = self.meta.path.last()?;
+      if let Some((_, sig)) = builtin_types.iter().find(|(n, _)| n == name) {
+        return types_from_signature(sig);
+      }
+      return None;
+    }
+
+    for alias in self.meta.aliases.as_ref().unwrap_or(&vec![]) {
+      if alias.len() == 2 && alias.first()? == "builtins" {
+        let name = alias.last()?;
+        if let Some((_, sig)) = builtin_types.iter().find(|(n, _)| n == name) {
+          return types_from_signature(sig);
+        }
+        return None;
+      }
+    }
+    None
+  }
+}
+
+/// function metadata
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Meta {
+  /// full path to the function
+  pub path: Vec<String>,
+  /// short description of what the function does
+  pub aliases: Option<Vec<Vec<String>>>,
+  /// function type signature
+  pub signature: Option<String>,
+  pub is_primop: Option<bool>,
+  /// primop metadata
+  pub primop_meta: Option<PrimopMeta>,
+}
+
+/// primop specific metadata
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct PrimopMeta {
+  /// function arguments
+  pub args: Option<Vec<String>>,
+}
+
+/// function content
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Content {
+  /// function description
+  pub content: Option<String>,
+}
+
+/// nix type
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Type {
+  Any,
+  Attrset,
+  Bool,
+  Derivation,
+  Float,
+  /// function from the first type to the second
+  Function(Box<Type>, Box<Type>),
+  List(Box<Type>),
+  Int,
+  Never,
+  Null,
+  Path,
+  String,
+  /// any of the types
+  Union(Vec<Type>),
+  /// type variable (e.g. `a` in `a -> [a]`)
+  Var(String),
+}
+
+impl Type {
+  /// parses a single type (e.g. `[ String ]`)
+  pub fn parse(s: &str) -> Option<Self> {
+    Self::from_expr(&signature::parse_type(s).ok()?)
+  }
+
+  /// turns a parsed type expression into a type
+  /// returns None for named types we don't know about
+  pub fn from_expr(expr: &TypeExpr) -> Option<Self> {
+    match expr {
+      TypeExpr::Named(name, args) => match name.to_lowercase().as_str() {
+        "any" => Some(Self::Any),
+        "attrs" | "attrset" | "attrsof" | "set" => Some(Self::Attrset),
+        "bool" | "boolean" => Some(Self::Bool),
+        "derivation" => Some(Self::Derivation),
+        "float" => Some(Self::Float),
+        "function" | "lambda" => Some(Self::Function(Box::new(Self::Any), Box::new(Self::Any))),
+        "int" | "integer" => Some(Self::Int),
+        "list" | "listof" => {
+          let inner = match args.first() {
+            Some(arg) => Self::from_expr(arg)?,
+            None => Self::Any,
+          };
+          Some(Self::List(Box::new(inner)))
+        }
+        "never" => Some(Self::Never),
+        "null" => Some(Self::Null),
+        "number" => Some(Self::Union(vec![Self::Int, Self::Float])),
+        "path" => Some(Self::Path),
+        "str" | "string" => Some(Self::String),
+        _ => None,
+      },
+      TypeExpr::Var(name) => Some(Self::Var(name.clone())),
+      TypeExpr::Attrset { .. } => Some(Self::Attrset),
+      TypeExpr::List(Some(inner)) => Some(Self::List(Box::new(Self::from_expr(inner)?))),
+      TypeExpr::List(None) => Some(Self::List(Box::new(Self::Any))),
+      TypeExpr::Function(arg, ret) => Some(Self::Function(
+        Box::new(Self::from_expr(arg)?),
+        Box::new(Self::from_expr(ret)?),
+      )),
+      TypeExpr::Union(types) => {
+        let mut union: Vec<Self> = Vec::new();
+        for ty in types {
+          let ty = match Self::from_expr(ty)? {
+            Self::Union(inner) => inner,
+            ty => vec![ty],
+          };
+          for ty in ty {
+            if !union.contains(&ty) {
+              union.push(ty);
+            }
+          }
+        }
+
+        if union.len() == 1 {
+          union.pop()
+        } else {
+          Some(Self::Union(union))
+        }
+      }
+    }
+  }
+}
+
+/// turns a signature into argument & output types *magic*
+/// the output is what's left after applying every argument
+pub fn types_from_signature(sig: &str) -> Option<(Vec<Type>, Type)> {
+  let sig = signature::parse(sig).ok()?;
+
+  let params = sig.ty.params();
+  if params.is_empty() {
+    return None;
+  }
+
+  let inputs = params
+    .into_iter()
+    .map(Type::from_expr)
+    .collect::<Option<Vec<_>>>()?;
+  let output = Type::from_expr(sig.ty.result())?;
+
+  Some((inputs, output))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn var(name: &str) -> Type {
+    Type::Var(name.to_string())
+  }
+
+  #[test]
+  fn new_variants_from_expr() {
+    assert!(
+      Type::parse("(a -> b) -> [a]")
+        == Some(Type::Function(
+          Box::new(Type::Function(Box::new(var("a")), Box::new(var("b")))),
+          Box::new(Type::List(Box::new(var("a"))))
+        ))
+    );
+    assert!(Type::parse("string | null") == Some(Type::Union(vec![Type::String, Type::Null])));
+    assert!(Type::parse("number | int") == Some(Type::Union(vec![Type::Int, Type::Float])));
+    assert!(Type::parse("never") == Some(Type::Never));
+    assert!(Type::parse("Maybe a").is_none());
+  }
+
+  #[test]
+  fn attrs_of_and_list_of_are_not_vars() {
+    assert!(Type::parse("attrsOf") == Some(Type::Attrset));
+    assert!(Type::parse("listOf") == Some(Type::List(Box::new(Type::Any))));
+    assert!(Type::parse("listOf int") == Some(Type::List(Box::new(Type::Int))));
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde_shape() {
+    let shapes = [
+      (Type::String, r#""string""#),
+      (Type::List(Box::new(Type::Int)), r#"{"list":"int"}"#),
+      (Type::Never, r#""never""#),
+      (var("a"), r#"{"var":"a"}"#),
+      (
+        Type::Union(vec![Type::String, Type::Null]),
+        r#"{"union":["string","null"]}"#,
+      ),
+      (
+        Type::Function(Box::new(var("a")), Box::new(Type::Bool)),
+        r#"{"function":[{"var":"a"},"bool"]}"#,
+      ),
+    ];
+
+    for (ty, json) in shapes {
+      assert_eq!(serde_json::to_string(&ty).unwrap(), json);
+      assert!(serde_json::from_str::<Type>(json).unwrap() == ty);
+    }
+  }
+}
@@ -0,0 +1,604 @@
+//! nix type signature parser
+
+use std::fmt;
+
+/// type names that are never treated as type variables, even when lowercase
+const BUILTIN_NAMES: &[&str] = &[
+  "any",
+  "attrs",
+  "attrset",
+  "attrsof",
+  "bool",
+  "boolean",
+  "derivation",
+  "float",
+  "function",
+  "int",
+  "integer",
+  "lambda",
+  "list",
+  "listof",
+  "never",
+  "null",
+  "number",
+  "path",
+  "set",
+  "str",
+  "string",
+];
+
+/// a parsed type signature
+/// e.g. `mapAttrs :: (String -> Any -> Any) -> AttrSet -> AttrSet`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+  /// name in front of the `::`, if there's one
+  pub name: Option<String>,
+  /// the type itself
+  pub ty: TypeExpr,
+}
+
+/// type expression tree
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeExpr {
+  /// named type, possibly applied to arguments (e.g. `String` or `Maybe a`)
+  Named(String, Vec<TypeExpr>),
+  /// type variable (e.g. `a`)
+  Var(String),
+  /// list with an optional element type (e.g. `[String]` or `[ ]`)
+  List(Option<Box<TypeExpr>>),
+  /// attrset literal (e.g. `{ name :: String; value :: a; ... }`)
+  Attrset { fields: Vec<Field>, open: bool },
+  /// function from the first type to the second (e.g. `a -> b`)
+  Function(Box<TypeExpr>, Box<TypeExpr>),
+  /// any of the types (e.g. `String | Null`)
+  Union(Vec<TypeExpr>),
+}
+
+/// attrset literal field
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+  pub name: String,
+  /// whether the field is marked with `?`
+  pub optional: bool,
+  /// field type, if given
+  pub ty: Option<TypeExpr>,
+}
+
+impl TypeExpr {
+  /// argument types of a curried function, empty if it's not a function
+  pub fn params(&self) -> Vec<&TypeExpr> {
+    let mut params = Vec::new();
+    let mut ty = self;
+    while let TypeExpr::Function(arg, ret) = ty {
+      params.push(arg.as_ref());
+      ty = ret;
+    }
+    params
+  }
+
+  /// what's left after applying every argument
+  pub fn result(&self) -> &TypeExpr {
+    let mut ty = self;
+    while let TypeExpr::Function(_, ret) = ty {
+      ty = ret;
+    }
+    ty
+  }
+
+  /// number of arguments a curried function takes
+  pub fn arity(&self) -> usize {
+    self.params().len()
+  }
+}
+
+/// why a signature couldn't be parsed
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+  /// byte offset into the signature
+  pub position: usize,
+  pub kind: ParseErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseErrorKind {
+  /// character that doesn't start any token
+  InvalidChar(char),
+  /// token where something else was expected
+  Unexpected {
+    found: String,
+    expected: &'static str,
+  },
+  /// signature ended where something else was expected
+  UnexpectedEnd { expected: &'static str },
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.kind {
+      ParseErrorKind::InvalidChar(c) => write!(f, "invalid character `{}` at {}", c, self.position),
+      ParseErrorKind::Unexpected { found, expected } => write!(
+        f,
+        "unexpected `{}` at {}, expected {}",
+        found, self.position, expected
+      ),
+      ParseErrorKind::UnexpectedEnd { expected } => {
+        write!(
+          f,
+          "unexpected end at {}, expected {}",
+          self.position, expected
+        )
+      }
+    }
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+/// parses a signature with an optional `name ::` in front
+pub fn parse(sig: &str) -> Result<Signature, ParseError> {
+  let mut parser = Parser::new(sig)?;
+
+  let name = match (parser.tokens.first(), parser.tokens.get(1)) {
+    (Some((_, Token::Ident(name))), Some((_, Token::DoubleColon))) => {
+      parser.pos = 2;
+      Some(name.to_string())
+    }
+    _ => None,
+  };
+
+  let ty = parser.ty()?;
+  parser.finish()?;
+
+  Ok(Signature { name, ty })
+}
+
+/// parses a bare type expression (e.g. `[ String ] -> String`)
+pub fn parse_type(s: &str) -> Result<TypeExpr, ParseError> {
+  let mut parser = Parser::new(s)?;
+  let ty = parser.ty()?;
+  parser.finish()?;
+  Ok(ty)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token<'a> {
+  Ident(&'a str),
+  /// `<...>`, used for things that don't have a proper type
+  Placeholder(&'a str),
+  Arrow,
+  DoubleColon,
+  Pipe,
+  Question,
+  Comma,
+  Semicolon,
+  Ellipsis,
+  LParen,
+  RParen,
+  LBracket,
+  RBracket,
+  LBrace,
+  RBrace,
+}
+
+impl fmt::Display for Token<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Token::Ident(s) => write!(f, "{}", s),
+      Token::Placeholder(s) => write!(f, "<{}>", s),
+      Token::Arrow => write!(f, "->"),
+      Token::DoubleColon => write!(f, "::"),
+      Token::Pipe => write!(f, "|"),
+      Token::Question => write!(f, "?"),
+      Token::Comma => write!(f, ","),
+      Token::Semicolon => write!(f, ";"),
+      Token::Ellipsis => write!(f, "..."),
+      Token::LParen => write!(f, "("),
+      Token::RParen => write!(f, ")"),
+      Token::LBracket => write!(f, "["),
+      Token::RBracket => write!(f, "]"),
+      Token::LBrace => write!(f, "{{"),
+      Token::RBrace => write!(f, "}}"),
+    }
+  }
+}
+
+fn tokenize(s: &str) -> Result<Vec<(usize, Token<'_>)>, ParseError> {
+  let mut tokens = Vec::new();
+  let mut chars = s.char_indices().peekable();
+
+  while let Some((start, c)) = chars.next() {
+    let token = match c {
+      c if c.is_whitespace() => continue,
+      '(' => Token::LParen,
+      ')' => Token::RParen,
+      '[' => Token::LBracket,
+      ']' => Token::RBracket,
+      '{' => Token::LBrace,
+      '}' => Token::RBrace,
+      '|' => Token::Pipe,
+      '?' => Token::Question,
+      ',' => Token::Comma,
+      ';' => Token::Semicolon,
+      '-' if s[start..].starts_with("->") => {
+        chars.next();
+        Token::Arrow
+      }
+      ':' if s[start..].starts_with("::") => {
+        chars.next();
+        Token::DoubleColon
+      }
+      '.' if s[start..].starts_with("...") => {
+        chars.next();
+        chars.next();
+        Token::Ellipsis
+      }
+      '<' => {
+        let end = s[start..].find('>').ok_or(ParseError {
+          position: s.len(),
+          kind: ParseErrorKind::UnexpectedEnd { expected: "`>`" },
+        })?;
+        while chars.next_if(|&(i, _)| i <= start + end).is_some() {}
+        Token::Placeholder(s[start + 1..start + end].trim())
+      }
+      c if c.is_alphabetic() || c == '_' => {
+        let mut end = start + c.len_utf8();
+        while let Some((i, c)) =
+          chars.next_if(|&(_, c)| c.is_alphanumeric() || c == '_' || c == '\'' || c == '.')
+        {
+          end = i + c.len_utf8();
+        }
+        Token::Ident(&s[start..end])
+      }
+      c => {
+        return Err(ParseError {
+          position: start,
+          kind: ParseErrorKind::InvalidChar(c),
+        });
+      }
+    };
+    tokens.push((start, token));
+  }
+
+  Ok(tokens)
+}
+
+struct Parser<'a> {
+  tokens: Vec<(usize, Token<'a>)>,
+  pos: usize,
+  len: usize,
+}
+
+impl<'a> Parser<'a> {
+  fn new(s: &'a str) -> Result<Self, ParseError> {
+    Ok(Self {
+      tokens: tokenize(s)?,
+      pos: 0,
+      len: s.len(),
+    })
+  }
+
+  fn peek(&self) -> Option<Token<'a>> {
+    self.tokens.get(self.pos).map(|(_, t)| *t)
+  }
+
+  fn bump(&mut self) {
+    self.pos += 1;
+  }
+
+  fn error(&self, expected: &'static str) -> ParseError {
+    match self.tokens.get(self.pos) {
+      Some((position, token)) => ParseError {
+        position: *position,
+        kind: ParseErrorKind::Unexpected {
+          found: token.to_string(),
+          expected,
+        },
+      },
+      None => ParseError {
+        position: self.len,
+        kind: ParseErrorKind::UnexpectedEnd { expected },
+      },
+    }
+  }
+
+  fn expect(&mut self, token: Token<'a>, expected: &'static str) -> Result<(), ParseError> {
+    if self.peek() == Some(token) {
+      self.bump();
+      Ok(())
+    } else {
+      Err(self.error(expected))
+    }
+  }
+
+  fn finish(&self) -> Result<(), ParseError> {
+    match self.peek() {
+      None => Ok(()),
+      Some(_) => Err(self.error("end of signature")),
+    }
+  }
+
+  /// type := union ('->' type)?
+  fn ty(&mut self) -> Result<TypeExpr, ParseError> {
+    let lhs = self.union()?;
+    if self.peek() == Some(Token::Arrow) {
+      self.bump();
+      let rhs = self.ty()?;
+      return Ok(TypeExpr::Function(Box::new(lhs), Box::new(rhs)));
+    }
+    Ok(lhs)
+  }
+
+  /// union := app ('|' app)*
+  fn union(&mut self) -> Result<TypeExpr, ParseError> {
+    let mut types = vec![self.app()?];
+    while self.peek() == Some(Token::Pipe) {
+      self.bump();
+      types.push(self.app()?);
+    }
+
+    if types.len() == 1 {
+      Ok(types.remove(0))
+    } else {
+      Ok(TypeExpr::Union(types))
+    }
+  }
+
+  /// app := ident atom* | atom
+  fn app(&mut self) -> Result<TypeExpr, ParseError> {
+    let Some(Token::Ident(name)) = self.peek() else {
+      return self.atom();
+    };
+    self.bump();
+
+    let mut args = Vec::new();
+    while matches!(
+      self.peek(),
+      Some(
+        Token::Ident(_) | Token::Placeholder(_) | Token::LParen | Token::LBracket | Token::LBrace
+      )
+    ) {
+      args.push(self.atom()?);
+    }
+
+    if args.is_empty() {
+      Ok(name_to_expr(name))
+    } else {
+      Ok(TypeExpr::Named(name.to_string(), args))
+    }
+  }
+
+  /// atom := ident | placeholder | '(' type ')' | '[' type? ']' | '{' fields '}'
+  fn atom(&mut self) -> Result<TypeExpr, ParseError> {
+    let token = self.peek().ok_or_else(|| self.error("a type"))?;
+
+    match token {
+      Token::Ident(name) => {
+        self.bump();
+        Ok(name_to_expr(name))
+      }
+      Token::Placeholder(name) => {
+        self.bump();
+        Ok(TypeExpr::Var(name.to_string()))
+      }
+      Token::LParen => {
+        self.bump();
+        let ty = self.ty()?;
+        self.expect(Token::RParen, "`)`")?;
+        Ok(ty)
+      }
+      Token::LBracket => {
+        self.bump();
+        if self.peek() == Some(Token::RBracket) {
+          self.bump();
+          return Ok(TypeExpr::List(None));
+        }
+        let ty = self.ty()?;
+        self.expect(Token::RBracket, "`]`")?;
+        Ok(TypeExpr::List(Some(Box::new(ty))))
+      }
+      Token::LBrace => {
+        self.bump();
+        self.fields()
+      }
+      _ => Err(self.error("a type")),
+    }
+  }
+
+  /// fields := ((ident '?'? ('::' type)? | '...') (',' | ';')?)* '}'
+  fn fields(&mut self) -> Result<TypeExpr, ParseError> {
+    let mut fields = Vec::new();
+    let mut open = false;
+
+    loop {
+      match self.peek() {
+        Some(Token::RBrace) => {
+          self.bump();
+          return Ok(TypeExpr::Attrset { fields, open });
+        }
+        Some(Token::Ellipsis) => {
+          self.bump();
+          open = true;
+        }
+        Some(Token::Ident(name)) => {
+          self.bump();
+
+          let optional = self.peek() == Some(Token::Question);
+          if optional {
+            self.bump();
+          }
+
+          let ty = if self.peek() == Some(Token::DoubleColon) {
+            self.bump();
+            Some(self.ty()?)
+          } else {
+            None
+          };
+
+          fields.push(Field {
+            name: name.to_string(),
+            optional,
+            ty,
+          });
+        }
+        _ => return Err(self.error("a field or `}`")),
+      }
+
+      match self.peek() {
+        Some(Token::Comma | Token::Semicolon) => self.bump(),
+        Some(Token::RBrace) => {}
+        _ => return Err(self.error("`,`, `;` or `}`")),
+      }
+    }
+  }
+}
+
+/// lowercase names that aren't builtin type names are type variables
+fn name_to_expr(name: &str) -> TypeExpr {
+  let is_builtin = BUILTIN_NAMES.contains(&name.to_lowercase().as_str());
+  if is_builtin || name.starts_with(char::is_uppercase) {
+    TypeExpr::Named(name.to_string(), Vec::new())
+  } else {
+    TypeExpr::Var(name.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn var(name: &str) -> TypeExpr {
+    TypeExpr::Var(name.to_string())
+  }
+
+  fn named(name: &str) -> TypeExpr {
+    TypeExpr::Named(name.to_string(), Vec::new())
+  }
+
+  fn func(arg: TypeExpr, ret: TypeExpr) -> TypeExpr {
+    TypeExpr::Function(Box::new(arg), Box::new(ret))
+  }
+
+  fn list(ty: TypeExpr) -> TypeExpr {
+    TypeExpr::List(Some(Box::new(ty)))
+  }
+
+  #[test]
+  fn arrows_are_right_associative() {
+    let ty = parse_type("a -> b -> c").unwrap();
+    assert_eq!(ty, func(var("a"), func(var("b"), var("c"))));
+    assert_eq!(ty.arity(), 2);
+    assert_eq!(ty.result(), &var("c"));
+  }
+
+  #[test]
+  fn parenthesized_function_argument() {
+    let ty = parse_type("(a -> b) -> [a] -> [b]").unwrap();
+    assert_eq!(
+      ty,
+      func(
+        func(var("a"), var("b")),
+        func(list(var("a")), list(var("b")))
+      )
+    );
+    assert_eq!(ty.params()[0], &func(var("a"), var("b")));
+  }
+
+  #[test]
+  fn attrset_with_nested_arrows() {
+    let ty = parse_type("{ f :: a -> b; x ? :: a, ... } -> b").unwrap();
+    assert_eq!(
+      ty,
+      func(
+        TypeExpr::Attrset {
+          fields: vec![
+            Field {
+              name: "f".to_string(),
+              optional: false,
+              ty: Some(func(var("a"), var("b"))),
+            },
+            Field {
+              name: "x".to_string(),
+              optional: true,
+              ty: Some(var("a")),
+            },
+          ],
+          open: true,
+        },
+        var("b")
+      )
+    );
+  }
+
+  #[test]
+  fn open_attrset() {
+    assert_eq!(
+      parse_type("{ ... }").unwrap(),
+      TypeExpr::Attrset {
+        fields: Vec::new(),
+        open: true
+      }
+    );
+  }
+
+  #[test]
+  fn union() {
+    assert_eq!(
+      parse_type("string | null").unwrap(),
+      TypeExpr::Union(vec![named("string"), named("null")])
+    );
+  }
+
+  #[test]
+  fn placeholder_is_a_var() {
+    assert_eq!(
+      parse_type("<placeholder> -> a").unwrap(),
+      func(var("placeholder"), var("a"))
+    );
+  }
+
+  #[test]
+  fn name_prefix() {
+    let sig = parse("hasPrefix :: String -> String -> Bool").unwrap();
+    assert_eq!(sig.name.as_deref(), Some("hasPrefix"));
+    assert_eq!(sig.ty.arity(), 2);
+    assert_eq!(sig.ty.result(), &named("Bool"));
+
+    assert!(parse("String -> Bool").unwrap().name.is_none());
+  }
+
+  #[test]
+  fn trailing_comment() {
+    assert_eq!(
+      parse_type("a -> b # comment").unwrap_err(),
+      ParseError {
+        position: 7,
+        kind: ParseErrorKind::InvalidChar('#'),
+      }
+    );
+  }
+
+  #[test]
+  fn unclosed_list() {
+    assert_eq!(
+      parse_type("[a").unwrap_err(),
+      ParseError {
+        position: 2,
+        kind: ParseErrorKind::UnexpectedEnd { expected: "`]`" },
+      }
+    );
+  }
+
+  #[test]
+  fn trailing_token() {
+    assert_eq!(
+      parse_type("a -> b )").unwrap_err(),
+      ParseError {
+        position: 7,
+        kind: ParseErrorKind::Unexpected {
+          found: ")".to_string(),
+          expected: "end of signature",
+        },
+      }
+    );
+  }
+}
@@ -1,4 +1,4 @@
-use crate::function::Type;
+use crate::{function::Type, provenance::Provenance};
 
 #[cfg(feature = "sqlx")]
 use sqlx::types::{Json, chrono};
@@ -8,47 +8,64 @@ use sqlx::types::{Json, chrono};
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Game {
-  /// this is alwals true
-  id: bool,
   /// full path to the function
   func: String,
   /// short description of what the function does
   description: String,
   /// number of arguments the function takes
   args: u8,
-  /// input type
+  /// argument types
   #[cfg(feature = "sqlx")]
-  input: Json<Type>,
+  inputs: Json<Vec<Type>>,
   #[cfg(not(feature = "sqlx"))]
-  input: Type,
+  inputs: Vec<Type>,
   /// output type
   #[cfg(feature = "sqlx")]
   output: Json<Type>,
   #[cfg(not(feature = "sqlx"))]
   output: Type,
-  /// nix commit hash
+  /// nixpkgs commit hash
   nix_commit: String,
+  /// nix version the builtins were taken from
+  nix_version: String,
+  /// when the dataset was generated
+  data_generated_at: Option<chrono::DateTime<chrono::Utc>>,
+  /// seed the function was picked with
+  seed: u32,
+  /// day the game is played on
+  date: chrono::NaiveDate,
   /// when the game was initialized
   created_at: chrono::NaiveDateTime,
 }
 
 impl Game {
   /// creates a new game instance
-  pub fn new(func: String, description: String, args: u8, input: Type, output: Type) -> Self {
+  pub fn new(
+    func: String,
+    description: String,
+    args: u8,
+    (inputs, output): (Vec<Type>, Type),
+    seed: u32,
+    date: chrono::NaiveDate,
+    provenance: &Provenance,
+  ) -> Self {
     Self {
-      id: true,
       func,
       description,
       args,
       #[cfg(feature = "sqlx")]
-      input: Json(input),
+      inputs: Json(inputs),
       #[cfg(not(feature = "sqlx"))]
-      input,
+      inputs,
       #[cfg(feature = "sqlx")]
       output: Json(output),
       #[cfg(not(feature = "sqlx"))]
       output,
-      nix_commit: String::new(), // TODO: implement this
+      nix_commit: provenance.nixpkgs_rev.clone(),
+      nix_version: provenance.nix_version.clone(),
+      data_generated_at: provenance.generated_at,
+      seed,
+      date,
       created_at: chrono::Utc::now().naive_utc(),
     }
   }
@@ -72,13 +89,25 @@ impl Game {
   pub fn get_args_count(&self) -> u8 {
     self.args
   }
-  pub fn get_types(&self) -> (&Type, &Type) {
-    (&self.input, &self.output)
+  pub fn get_types(&self) -> (&[Type], &Type) {
+    (&self.inputs, &self.output)
   }
   pub fn get_nix_commit(&self) -> &str {
     &self.nix_commit
   }
+  pub fn get_nix_version(&self) -> &str {
+    &self.nix_version
+  }
+  pub fn get_data_generated_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+    self.data_generated_at
+  }
+  pub fn get_seed(&self) -> u32 {
+    self.seed
+  }
   pub fn get_date(&self) -> String {
-    self.created_at.format("%Y-%m-%d").to_string()
+    self.date.format("%Y-%m-%d").to_string()
+  }
+  pub fn get_created_at(&self) -> chrono::NaiveDateTime {
+    self.created_at
   }
 }
@@ -1,38 +1,101 @@
 //! nixdle - wordle but it's nix functions
 
-use rand::prelude::IndexedRandom;
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use hmac::{Hmac, Mac};
+use rand::{SeedableRng, prelude::IndexedRandom};
+use rand_chacha::ChaCha8Rng;
+use sha2::Sha256;
 
 pub mod api;
 pub mod function;
 pub mod game;
+pub mod provenance;
+pub mod search;
 
-use api::{AttemptMessage, Matches, StartMessage};
+use api::{AttemptMessage, AttemptResponse, Matches, RevealMessage, StartMessage, TypeMatch};
 use function::Function;
 use game::Game;
+use provenance::Provenance;
 
 pub const NEXT_CLUE_ATTEMPTS: usize = 5;
 
+/// guesses left until the next clue, given the clues & attempts so far
+/// an attempt is judged before it's counted, so the nth clue comes with attempt n * NEXT_CLUE_ATTEMPTS + 1
+pub fn attempts_until_next_clue(clues: usize, attempts: usize) -> usize {
+  ((clues + 1) * NEXT_CLUE_ATTEMPTS + 1).saturating_sub(attempts)
+}
+
+/// how many functions to suggest for an unknown guess
+pub const SUGGESTIONS: usize = 3;
+
 /// contains everything needed to run
 #[derive(Clone)]
 pub struct State {
   pub game: Option<Game>,
   pub functions: Vec<Function>,
   pub builtin_types: Vec<(String, String)>,
+  pub provenance: Provenance,
+  /// lowercase full paths, names & aliases to ids in `functions`, own paths before aliases
+  index: HashMap<String, Vec<usize>>,
+  /// ids of `functions` to the id of the shortest path among everything they're aliases of
+  canonical: Vec<usize>,
+  /// full paths of `functions`, joined once for suggestions
+  paths: Vec<String>,
 }
 
 impl State {
   /// creates a new state
-  pub fn new(functions: Vec<Function>, builtin_types: Vec<(String, String)>) -> Self {
+  pub fn new(
+    functions: Vec<Function>,
+    builtin_types: Vec<(String, String)>,
+    provenance: Provenance,
+  ) -> Self {
     Self {
       game: None,
+      index: build_index(&functions),
+      canonical: build_canonical(&functions),
+      paths: functions.iter().map(|f| f.meta.path.join(".")).collect(),
       functions,
       builtin_types,
+      provenance,
     }
   }
 
   /// initializes a new random game from available functions
   pub fn init_random_game(&mut self) {
-    let rng = &mut rand::rng();
+    self.game = Some(self.random_game());
+  }
+
+  /// creates a random game without replacing the current one (e.g. for practice)
+  pub fn random_game(&self) -> Game {
+    self.seeded_game(rand::random(), chrono::Utc::now().date_naive())
+  }
+
+  /// initializes the daily game for the given date
+  /// returns the seed the function was picked with
+  pub fn init_daily_game(&mut self, date: NaiveDate, salt: &str) -> u32 {
+    let game = self.daily_game(date, salt);
+    let seed = game.get_seed();
+    self.game = Some(game);
+    seed
+  }
+
+  /// initializes a game from a known seed (e.g. to regenerate an archived day)
+  pub fn init_seeded_game(&mut self, seed: u32, date: NaiveDate) {
+    self.game = Some(self.seeded_game(seed, date));
+  }
+
+  /// creates the daily game for the given date without replacing the current one
+  /// every instance with the same functions & salt picks the same function
+  pub fn daily_game(&self, date: NaiveDate, salt: &str) -> Game {
+    self.seeded_game(daily_seed(date, salt), date)
+  }
+
+  /// creates a game from a known seed without replacing the current one
+  pub fn seeded_game(&self, seed: u32, date: NaiveDate) -> Game {
+    let rng = &mut ChaCha8Rng::seed_from_u64(seed as u64);
 
     loop {
       let func = self.functions.choose(rng).expect("where functions??");
@@ -54,62 +117,105 @@ impl State {
         None => continue,
       };
 
-      self.game = Some(Game::new(
+      return Game::new(
         func.meta.path.join("."),
         desc.trim().to_string(),
         args,
-        types.0,
-        types.1,
-      ));
-
-      break;
+        types,
+        seed,
+        date,
+        &self.provenance,
+      );
     }
   }
 
   /// starts a new game attempt
-  pub fn start_game(&self, attempt_url: String) -> StartMessage {
+  pub fn start_game(
+    &self,
+    attempt_url: String,
+    giveup_url: String,
+    session: String,
+  ) -> StartMessage {
     let game = self.game.as_ref().expect("where game??");
+    self.start(game, attempt_url, giveup_url, session)
+  }
+
+  /// starts an attempt at any game (e.g. one from the archive)
+  pub fn start(
+    &self,
+    game: &Game,
+    attempt_url: String,
+    giveup_url: String,
+    session: String,
+  ) -> StartMessage {
     StartMessage {
       date: game.get_date(),
+      session,
       attempt_url,
+      giveup_url,
       possible_clues: game.get_clues().len() as u8,
       rules: format!(
-        "you can guess by full path (e.g. 'lib.replaceStrings')\nor by name (e.g. 'substring' for 'builtins.substring')\nafter each guess, you'll see how close you were to the actual function\nevery {} attempts, you'll get a new path clue",
+        "you can guess by full path (e.g. 'lib.replaceStrings')\nor by name (e.g. 'substring' for 'builtins.substring')\nif a name is used in more than one place, you'll be asked which one you meant\nafter each guess, you'll see how close you were to the actual function\nevery {} attempts, you'll get a new path clue",
         NEXT_CLUE_ATTEMPTS
       ),
       version: env!("CARGO_PKG_VERSION").to_string(),
       nix_commit: game.get_nix_commit().to_string(),
+      nix_version: game.get_nix_version().to_string(),
+      data_generated_at: game.get_data_generated_at(),
     }
   }
 
   /// attempts to guess the function
-  /// returns None if the guess is invalid (i.e. not a known function)
-  pub fn attempt_game(&self, input: &str, attempts: usize) -> Option<AttemptMessage> {
-    let input = input.trim().to_lowercase();
-    let game = self.game.as_ref().expect("where game??");
+  /// `attempts` is the number of valid attempts made before this one
+  /// unknown guesses get the closest known functions instead
+  /// and names used by several functions get all of them to pick from
+  pub fn attempt_game(&self, input: &str, attempts: usize) -> AttemptResponse {
+    self.attempt(self.game.as_ref().expect("where game??"), input, attempts)
+  }
+
+  /// attempts to guess the function of any game (e.g. one from the archive)
+  pub fn attempt(&self, game: &Game, input: &str, attempts: usize) -> AttemptResponse {
+    match self.resolve_ids(input).as_slice() {
+      [] => AttemptResponse::Unknown(self.suggest(input, SUGGESTIONS)),
+      &[id] => match self.judge(game, id, attempts) {
+        Some(msg) => AttemptResponse::Attempt(msg),
+        None => AttemptResponse::Unknown(self.suggest(input, SUGGESTIONS)),
+      },
+      ids => AttemptResponse::Ambiguous(ids.iter().map(|&id| self.paths[id].clone()).collect()),
+    }
+  }
+
+  /// reveals the answer of a game
+  pub fn reveal(&self, game: &Game) -> RevealMessage {
+    RevealMessage {
+      name: game.get_func_name().unwrap_or_default().to_string(),
+      func: game.get_func().to_string(),
+      description: game.get_description().to_string(),
+    }
+  }
+
+  /// judges a guess against a game
+  /// returns None if the guess can't be compared (i.e. it has no types)
+  fn judge(&self, game: &Game, guess_id: usize, attempts: usize) -> Option<AttemptMessage> {
     let func = game.get_func();
     let all_clues = game.get_clues();
 
-    let guess_func = self.find_function(&input)?;
-
-    if input == func
-      || guess_func.meta.path.join(".") == func
-      || guess_func
-        .meta
-        .aliases
-        .as_ref()
-        .unwrap_or(&vec![])
-        .iter()
-        .any(|a| a.join(".") == func)
-    {
+    let guess_func = &self.functions[guess_id];
+    let guess = self.paths[guess_id].clone();
+
+    // the answer itself or anything in its alias group, whichever way the alias goes
+    let answer_id = self.lookup(func).first().copied();
+    if answer_id.is_some_and(|id| self.canonical[id] == self.canonical[guess_id]) {
       return Some(AttemptMessage {
         success: true,
+        guess,
         func: Some(game.get_func().to_string()),
         description: Some(game.get_description().to_string()),
         clues: all_clues,
         args: Matches::JustRight,
-        input: true,
-        output: true,
+        inputs: vec![TypeMatch::Exact; game.get_types().0.len()],
+        output: TypeMatch::Exact,
+        token: None,
       });
     }
 
@@ -123,31 +229,162 @@ impl State {
 
     Some(AttemptMessage {
       success: false,
+      guess,
       func: None,
       description: None,
       clues,
       args: args_match,
-      input: types_match.0,
+      inputs: types_match.0,
       output: types_match.1,
+      token: None,
     })
   }
 
-  /// finds a function by its full path (e.g. "lib.mapAttrs")
-  /// or name (e.g. "substring" for "builtins.substring" or "flip" for "lib.flip")
-  /// returns None if not found
+  /// full paths of every function that can be guessed
+  pub fn paths(&self) -> &[String] {
+    &self.paths
+  }
+
+  /// finds the known functions closest to the input, best first
+  pub fn suggest(&self, input: &str, limit: usize) -> Vec<String> {
+    search::rank(input, self.paths.iter().map(String::as_str), limit)
+      .into_iter()
+      .map(str::to_string)
+      .collect()
+  }
+
+  /// finds a function by its full path (e.g. "lib.mapAttrs"), one of its aliases
+  /// or name (e.g. "substring" for "builtins.substring" or "hasPrefix" for "lib.strings.hasPrefix")
+  /// ignores case, returns None if not found or if the name is ambiguous
   pub fn find_function(&self, path: &str) -> Option<&Function> {
+    match self.resolve_ids(path).as_slice() {
+      &[id] => Some(&self.functions[id]),
+      _ => None,
+    }
+  }
+
+  /// finds every distinct function the input could refer to, best first
+  /// functions that are aliases of each other count as one
+  pub fn resolve(&self, path: &str) -> Vec<&Function> {
+    self
+      .resolve_ids(path)
+      .into_iter()
+      .map(|id| &self.functions[id])
+      .collect()
+  }
+
+  /// ids of the distinct functions the input could refer to
+  /// a full path means exactly one function, a name may be used in several namespaces
+  fn resolve_ids(&self, path: &str) -> Vec<usize> {
+    let ids = self.lookup(path);
     if path.contains('.') {
-      self
-        .functions
-        .iter()
-        .find(|f| f.meta.path.join(".") == path)
-    } else {
-      self
-        .functions
-        .iter()
-        .find(|f| f.meta.path.len() == 2 && f.meta.path.last() == Some(&path.to_string()))
+      return ids.first().copied().into_iter().collect();
+    }
+
+    let mut resolved = Vec::new();
+    for &id in ids {
+      let id = self.canonical[id];
+      if !resolved.contains(&id) {
+        resolved.push(id);
+      }
     }
+    resolved
   }
+
+  /// ids of all functions matching a path, alias or name, best first
+  fn lookup(&self, path: &str) -> &[usize] {
+    self
+      .index
+      .get(&path.trim().to_lowercase())
+      .map(Vec::as_slice)
+      .unwrap_or_default()
+  }
+}
+
+/// ids of functions ordered by path length, so shorter paths come first
+fn by_depth(functions: &[Function]) -> Vec<usize> {
+  let mut ids: Vec<usize> = (0..functions.len()).collect();
+  ids.sort_by_key(|&id| functions[id].meta.path.len());
+  ids
+}
+
+/// maps lowercase full paths, aliases & names to function ids
+/// all own paths go in before any alias so they win lookups
+fn build_index(functions: &[Function]) -> HashMap<String, Vec<usize>> {
+  let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+  let mut insert = |path: &[String], id: usize| {
+    let keys = [path.join("."), path.last().cloned().unwrap_or_default()];
+    for key in keys {
+      let ids = index.entry(key.to_lowercase()).or_default();
+      if !ids.contains(&id) {
+        ids.push(id);
+      }
+    }
+  };
+
+  let order = by_depth(functions);
+  for &id in &order {
+    insert(&functions[id].meta.path, id);
+  }
+  for &id in &order {
+    for alias in functions[id].meta.aliases.iter().flatten() {
+      insert(alias, id);
+    }
+  }
+
+  index
+}
+
+/// groups functions that are aliases of each other (e.g. lib.flip & lib.trivial.flip)
+/// and maps each id to the group member with the shortest path
+fn build_canonical(functions: &[Function]) -> Vec<usize> {
+  fn root(parent: &mut [usize], mut id: usize) -> usize {
+    while parent[id] != id {
+      parent[id] = parent[parent[id]];
+      id = parent[id];
+    }
+    id
+  }
+
+  let mut rank = vec![0; functions.len()];
+  for (r, id) in by_depth(functions).into_iter().enumerate() {
+    rank[id] = r;
+  }
+  let ids: HashMap<String, usize> = functions
+    .iter()
+    .enumerate()
+    .map(|(id, f)| (f.meta.path.join(".").to_lowercase(), id))
+    .collect();
+
+  let mut parent: Vec<usize> = (0..functions.len()).collect();
+  for (id, f) in functions.iter().enumerate() {
+    for alias in f.meta.aliases.iter().flatten() {
+      let Some(&other) = ids.get(&alias.join(".").to_lowercase()) else {
+        continue;
+      };
+      let (a, b) = (root(&mut parent, id), root(&mut parent, other));
+      if rank[a] < rank[b] {
+        parent[b] = a;
+      } else if a != b {
+        parent[a] = b;
+      }
+    }
+  }
+
+  (0..functions.len())
+    .map(|id| root(&mut parent, id))
+    .collect()
+}
+
+/// derives the seed of the daily game for the given date
+/// the salt should be kept secret, otherwise anyone can work out future games
+pub fn daily_seed(date: NaiveDate, salt: &str) -> u32 {
+  let mut mac =
+    Hmac::<Sha256>::new_from_slice(salt.as_bytes()).expect("hmac takes keys of any size");
+  mac.update(date.format("%Y-%m-%d").to_string().as_bytes());
+  let hash = mac.finalize().into_bytes();
+
+  u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
 }
 
 /// parse functions from JSON data and filter out those without description or types
@@ -170,6 +407,12 @@ pub fn parse_functions_filtered(
   Ok(filtered)
 }
 
+/// parse dataset provenance from the JSON manifest shipped next to the data
+#[cfg(feature = "serde")]
+pub fn parse_provenance(data: &str) -> Result<Provenance, serde_json::Error> {
+  serde_json::from_str(data)
+}
+
 /// parse builtin types from JSON data
 #[cfg(feature = "serde")]
 pub fn parse_builtin_types(data: &str) -> Result<Vec<(String, String)>, serde_json::Error> {
@@ -188,3 +431,279 @@ pub fn parse_builtin_types(data: &str) -> Result<Vec<(String, String)>, serde_js
 
   Ok(types)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use function::{Content, Meta, PrimopMeta};
+
+  fn func(path: &str, signature: Option<&str>, aliases: &[&str]) -> Function {
+    let split = |p: &str| p.split('.').map(str::to_string).collect::<Vec<_>>();
+    Function {
+      meta: Meta {
+        path: split(path),
+        aliases: Some(aliases.iter().map(|a| split(a)).collect()),
+        signature: signature.map(str::to_string),
+        is_primop: None,
+        primop_meta: None,
+      },
+      content: Some(Content {
+        content: Some(format!("{} does things", path)),
+      }),
+    }
+  }
+
+  fn game_state(answer: &str) -> State {
+    let mut substring = func("builtins.substring", None, &["lib.strings.substring"]);
+    substring.meta.primop_meta = Some(PrimopMeta {
+      args: Some(vec!["start".into(), "len".into(), "s".into()]),
+    });
+
+    let functions = vec![
+      func(
+        "lib.mapAttrs",
+        Some("mapAttrs :: (String -> Any -> Any) -> AttrSet -> AttrSet"),
+        &["lib.attrsets.mapAttrs", "builtins.mapAttrs"],
+      ),
+      func(
+        "lib.strings.concatMapStrings",
+        Some("concatMapStrings :: (a -> string) -> [a] -> string"),
+        &["lib.concatMapStrings"],
+      ),
+      substring,
+      func(
+        "lib.strings.hasPrefix",
+        Some("hasPrefix :: string -> string -> bool"),
+        &["lib.hasPrefix"],
+      ),
+      func(
+        "lib.path.hasPrefix",
+        Some("hasPrefix :: Path -> Path -> Bool"),
+        &[],
+      ),
+      func(
+        "lib.flip",
+        Some("flip :: (a -> b -> c) -> b -> a -> c"),
+        &["lib.trivial.flip"],
+      ),
+      func(
+        "lib.trivial.flip",
+        Some("flip :: (a -> b -> c) -> b -> a -> c"),
+        &["lib.flip"],
+      ),
+    ];
+    let builtin_types = vec![(
+      "substring".to_string(),
+      "Int -> Int -> String -> String".to_string(),
+    )];
+
+    with_game(
+      State::new(functions, builtin_types, Provenance::default()),
+      answer,
+    )
+  }
+
+  fn with_game(mut state: State, answer: &str) -> State {
+    let answer = state.find_function(answer).unwrap();
+    state.game = Some(Game::new(
+      answer.meta.path.join("."),
+      answer.get_description().unwrap().to_string(),
+      answer.get_args_count() as u8,
+      answer.get_types(&state.builtin_types).unwrap(),
+      0,
+      NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+      &state.provenance,
+    ));
+    state
+  }
+
+  fn attempt(state: &State, input: &str) -> AttemptMessage {
+    match state.attempt_game(input, 0) {
+      AttemptResponse::Attempt(msg) => msg,
+      _ => panic!("{} should be known", input),
+    }
+  }
+
+  #[test]
+  fn find_function_ignores_case() {
+    let state = game_state("lib.mapAttrs");
+
+    for input in ["lib.mapAttrs", "lib.mapattrs", "LIB.MAPATTRS"] {
+      let found = state.find_function(input).unwrap();
+      assert_eq!(found.meta.path.join("."), "lib.mapAttrs");
+    }
+  }
+
+  #[test]
+  fn find_function_by_name_ignores_case() {
+    let state = game_state("lib.mapAttrs");
+
+    for input in ["substring", "SubString"] {
+      let found = state.find_function(input).unwrap();
+      assert_eq!(found.meta.path.join("."), "builtins.substring");
+    }
+  }
+
+  #[test]
+  fn find_function_by_alias() {
+    let state = game_state("lib.mapAttrs");
+
+    let found = state.find_function("builtins.mapattrs").unwrap();
+    assert_eq!(found.meta.path.join("."), "lib.mapAttrs");
+    let found = state.find_function("lib.concatmapstrings").unwrap();
+    assert_eq!(found.meta.path.join("."), "lib.strings.concatMapStrings");
+    let found = state.find_function("lib.strings.substring").unwrap();
+    assert_eq!(found.meta.path.join("."), "builtins.substring");
+  }
+
+  #[test]
+  fn find_function_by_nested_name() {
+    let state = game_state("lib.mapAttrs");
+
+    let found = state.find_function("concatmapstrings").unwrap();
+    assert_eq!(found.meta.path.join("."), "lib.strings.concatMapStrings");
+  }
+
+  #[test]
+  fn resolve_collapses_aliases() {
+    let state = game_state("lib.mapAttrs");
+
+    let found = state.resolve("flip");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].meta.path.join("."), "lib.flip");
+  }
+
+  #[test]
+  fn attempt_ambiguous_name() {
+    let state = game_state("lib.mapAttrs");
+
+    assert!(state.find_function("hasPrefix").is_none());
+    match state.attempt_game("hasprefix", 0) {
+      AttemptResponse::Ambiguous(paths) => {
+        assert_eq!(paths, ["lib.strings.hasPrefix", "lib.path.hasPrefix"])
+      }
+      _ => panic!("hasprefix should be ambiguous"),
+    }
+
+    let msg = attempt(&state, "lib.path.hasPrefix");
+    assert_eq!(msg.guess, "lib.path.hasPrefix");
+  }
+
+  #[test]
+  fn attempt_returns_canonical_casing() {
+    let state = game_state("lib.mapAttrs");
+
+    let msg = attempt(&state, "lib.mapattrs");
+    assert!(msg.success);
+    assert_eq!(msg.guess, "lib.mapAttrs");
+    assert_eq!(msg.func.as_deref(), Some("lib.mapAttrs"));
+
+    let msg = attempt(&state, "builtins.SUBSTRING");
+    assert!(!msg.success);
+    assert_eq!(msg.guess, "builtins.substring");
+  }
+
+  #[test]
+  fn attempt_by_alias_of_answer() {
+    let state = game_state("lib.strings.concatMapStrings");
+
+    let msg = attempt(&state, "lib.concatmapstrings");
+    assert!(msg.success);
+    assert_eq!(msg.guess, "lib.strings.concatMapStrings");
+
+    let state = game_state("lib.mapAttrs");
+
+    let msg = attempt(&state, "lib.attrsets.mapAttrs");
+    assert!(msg.success);
+    assert_eq!(msg.guess, "lib.mapAttrs");
+  }
+
+  #[test]
+  fn attempt_by_one_way_alias() {
+    let signature = Some("flip :: (a -> b -> c) -> b -> a -> c");
+    let functions = vec![
+      func("lib.flip", signature, &[]),
+      func("lib.trivial.flip", signature, &["lib.flip"]),
+    ];
+
+    for answer in ["lib.flip", "lib.trivial.flip"] {
+      let state = with_game(
+        State::new(functions.clone(), Vec::new(), Provenance::default()),
+        answer,
+      );
+
+      let msg = attempt(&state, "flip");
+      assert!(msg.success, "flip should win against {}", answer);
+      assert_eq!(msg.guess, "lib.flip");
+      assert!(attempt(&state, "lib.trivial.flip").success);
+      assert!(attempt(&state, "lib.flip").success);
+    }
+  }
+
+  #[test]
+  fn attempt_unknown_function() {
+    let state = game_state("lib.mapAttrs");
+
+    assert!(matches!(
+      state.attempt_game("lib.mapAttributes", 0),
+      AttemptResponse::Unknown(_)
+    ));
+  }
+
+  #[test]
+  fn daily_game_is_the_same_everywhere() {
+    let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    let a = game_state("lib.flip").daily_game(date, "salt");
+    let b = game_state("lib.mapAttrs").daily_game(date, "salt");
+
+    assert_eq!(a.get_func(), b.get_func());
+    assert_eq!(a.get_seed(), b.get_seed());
+    assert_eq!(a.get_seed(), daily_seed(date, "salt"));
+  }
+
+  #[test]
+  fn daily_seed_depends_on_date_and_salt() {
+    let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    let seed = daily_seed(date, "salt");
+
+    assert_ne!(seed, daily_seed(date, "pepper"));
+    assert_ne!(seed, daily_seed(date.succ_opt().unwrap(), "salt"));
+  }
+
+  #[test]
+  fn seeded_game_regenerates_daily_game() {
+    let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    let mut state = game_state("lib.flip");
+    let game = state.daily_game(date, "salt");
+
+    state.init_seeded_game(game.get_seed(), date);
+    let regenerated = state.game.as_ref().unwrap();
+    assert_eq!(regenerated.get_func(), game.get_func());
+    assert_eq!(regenerated.get_date(), game.get_date());
+    assert!(regenerated.get_types() == game.get_types());
+  }
+
+  #[test]
+  fn next_clue_countdown() {
+    assert_eq!(attempts_until_next_clue(0, 0), 6);
+    assert_eq!(attempts_until_next_clue(0, 4), 2);
+    assert_eq!(attempts_until_next_clue(0, 5), 1);
+    assert_eq!(attempts_until_next_clue(1, 6), 5);
+  }
+
+  #[test]
+  fn next_clue_countdown_agrees_with_judging() {
+    let state = game_state("lib.strings.hasPrefix");
+    let mut clues = 0;
+
+    for attempts in 0..2 * NEXT_CLUE_ATTEMPTS + 1 {
+      let next_in = attempts_until_next_clue(clues, attempts);
+      let AttemptResponse::Attempt(msg) = state.attempt_game("lib.flip", attempts) else {
+        panic!("lib.flip should be known");
+      };
+      assert_eq!(msg.clues.len() > clues, next_in == 1);
+      clues = msg.clues.len();
+    }
+    assert_eq!(clues, 2);
+  }
+}
@@ -0,0 +1,14 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// where the function dataset came from
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Provenance {
+  /// nixpkgs revision the functions were taken from
+  pub nixpkgs_rev: String,
+  /// nix (or lix) version the builtins were taken from
+  pub nix_version: String,
+  /// when the nixpkgs the dataset was generated from was last modified
+  pub generated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
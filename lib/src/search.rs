@@ -0,0 +1,179 @@
+//! fuzzy matching of guesses against function paths
+
+/// ranks candidates by how well they match the input, best first
+/// returns at most `limit` candidates, leaving out those that don't match at all
+pub fn rank<'a>(
+  input: &str,
+  candidates: impl IntoIterator<Item = &'a str>,
+  limit: usize,
+) -> Vec<&'a str> {
+  let input = input.trim();
+  if input.is_empty() {
+    return Vec::new();
+  }
+
+  let mut scored: Vec<(usize, &str)> = candidates
+    .into_iter()
+    .filter_map(|c| score(input, c).map(|s| (s, c)))
+    .collect();
+  scored.sort_by(|a, b| {
+    a.0
+      .cmp(&b.0)
+      .then(a.1.len().cmp(&b.1.len()))
+      .then(a.1.cmp(b.1))
+  });
+  scored.dedup_by(|a, b| a.1 == b.1);
+
+  scored.into_iter().take(limit).map(|(_, c)| c).collect()
+}
+
+/// how well a candidate path matches the input, lower is better
+/// returns None if it doesn't match at all
+///
+/// matches are tiered: exact (ignoring case) < prefix < camelCase humps < edit distance
+fn score(input: &str, candidate: &str) -> Option<usize> {
+  const TIER: usize = 1000;
+
+  let input = input.to_lowercase();
+  let name = candidate.rsplit('.').next().unwrap_or(candidate);
+  // with a dot in the input the whole path is compared, otherwise only the name
+  let target = if input.contains('.') { candidate } else { name };
+  let target_lower = target.to_lowercase();
+
+  if target_lower == input {
+    return Some(0);
+  }
+
+  if target_lower.starts_with(&input) {
+    return Some(TIER + target.len() - input.len());
+  }
+
+  if let Some(skipped) = camel_match(&input, &camel_words(name)) {
+    return Some(2 * TIER + skipped);
+  }
+
+  let distance = levenshtein(&input, &target_lower);
+  if distance <= (input.chars().count() / 3).max(1) {
+    return Some(3 * TIER + distance);
+  }
+
+  None
+}
+
+/// splits a camelCase name into lowercase words (e.g. `concatMapStrings` -> concat, map, strings)
+fn camel_words(name: &str) -> Vec<String> {
+  let mut words: Vec<String> = Vec::new();
+  for c in name.chars() {
+    match words.last_mut() {
+      Some(word) if !c.is_uppercase() && c != '_' && c != '-' => word.extend(c.to_lowercase()),
+      _ if c == '_' || c == '-' => words.push(String::new()),
+      _ => words.push(c.to_lowercase().collect()),
+    }
+  }
+  words.retain(|w| !w.is_empty());
+  words
+}
+
+/// matches the input against prefixes of consecutive-ish words
+/// (e.g. `cms` or `conMapStr` against concat, map, strings)
+/// returns the number of words skipped, None if it doesn't match
+fn camel_match(input: &str, words: &[String]) -> Option<usize> {
+  if input.is_empty() {
+    return Some(0);
+  }
+  let (word, rest) = words.split_first()?;
+
+  let common = input
+    .chars()
+    .zip(word.chars())
+    .take_while(|(a, b)| a == b)
+    .count();
+
+  // take as much of the word as possible first, then back off
+  let taken = (1..=common).rev().find_map(|n| {
+    let split = input
+      .char_indices()
+      .nth(n)
+      .map(|(i, _)| i)
+      .unwrap_or(input.len());
+    camel_match(&input[split..], rest)
+  });
+
+  taken.or_else(|| camel_match(input, rest).map(|s| s + 1))
+}
+
+/// edit distance between two strings
+fn levenshtein(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut row: Vec<usize> = (0..=b.len()).collect();
+
+  for (i, ca) in a.chars().enumerate() {
+    let mut prev = row[0];
+    row[0] = i + 1;
+    for (j, cb) in b.iter().enumerate() {
+      let cost = if ca == *cb { prev } else { prev + 1 };
+      prev = row[j + 1];
+      row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+    }
+  }
+
+  row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tiers() {
+    let candidates = [
+      "lib.max",
+      "lib.concatMap",
+      "lib.mapAttrs",
+      "lib.map",
+      "lib.flip",
+    ];
+    assert_eq!(
+      rank("map", candidates, 10),
+      ["lib.map", "lib.mapAttrs", "lib.concatMap", "lib.max"]
+    );
+  }
+
+  #[test]
+  fn ignore_case() {
+    assert_eq!(score("MAPATTRS", "lib.mapAttrs"), Some(0));
+  }
+
+  #[test]
+  fn camel_case_humps() {
+    assert_eq!(score("cms", "lib.concatMapStrings"), Some(2000));
+    assert_eq!(score("conMapStr", "lib.concatMapStrings"), Some(2000));
+    assert_eq!(score("ms", "lib.concatMapStrings"), Some(2001));
+  }
+
+  #[test]
+  fn edit_distance_threshold() {
+    // a third of the input, at least one
+    assert_eq!(score("map", "lib.max"), Some(3001));
+    assert_eq!(score("map", "lib.mox"), None);
+    assert_eq!(score("strings", "lib.stxxngs"), Some(3002));
+    assert_eq!(score("strings", "lib.sxxxngs"), None);
+  }
+
+  #[test]
+  fn dots_compare_the_whole_path() {
+    assert_eq!(score("map", "lib.lists.map"), Some(0));
+    assert_eq!(score("lib.map", "lib.lists.map"), None);
+    assert_eq!(score("lib.lists.map", "lib.lists.map"), Some(0));
+    assert!(score("lib.li", "lib.lists.map").is_some());
+    assert_eq!(score("li", "lib.lists.map"), None);
+  }
+
+  #[test]
+  fn limit_and_dedup() {
+    let candidates = ["lib.map", "lib.mapAttrs", "lib.map", "lib.max"];
+    assert_eq!(rank("map", candidates, 2), ["lib.map", "lib.mapAttrs"]);
+    assert_eq!(rank("map", candidates, 10).len(), 3);
+    assert!(rank("  ", candidates, 10).is_empty());
+  }
+}
@@ -1,6 +1,7 @@
 {
   lib,
   rustPlatform,
+  data,
 }:
 
 let
@@ -17,6 +18,8 @@ rustPlatform.buildRustPackage {
 
   doCheck = false;
 
+  env.NIXDLE_DATA_DIR = data;
+
   meta = {
     inherit (p) description;
     homepage = p.repository;
@@ -3,6 +3,9 @@
   pasta,
   pesto,
   salt,
+  nixpkgs-rev,
+  nixpkgs-last-modified,
+  nix-version,
 }:
 
 stdenvNoCC.mkDerivation {
@@ -16,6 +19,14 @@ stdenvNoCC.mkDerivation {
     cp ${salt}/builtins.types.json $out/builtin_types.json
     ${pesto}/bin/pesto --pos-file ${pasta} --format json --language ${salt}/language.json $out/functions.json
 
+    cat > $out/manifest.json <<EOF
+    {
+      "nixpkgs_rev": "${nixpkgs-rev}",
+      "nix_version": "${nix-version}",
+      "generated_at": "$(date -u -d @${toString nixpkgs-last-modified} +%Y-%m-%dT%H:%M:%SZ)"
+    }
+    EOF
+
     runHook postBuild
   '';
 }
@@ -0,0 +1,14 @@
+-- one game per day
+CREATE TABLE games (
+  date TEXT PRIMARY KEY NOT NULL,
+  func TEXT NOT NULL,
+  description TEXT NOT NULL,
+  args INTEGER NOT NULL,
+  inputs TEXT NOT NULL,
+  output TEXT NOT NULL,
+  nix_commit TEXT NOT NULL,
+  nix_version TEXT NOT NULL,
+  data_generated_at TEXT,
+  seed INTEGER NOT NULL,
+  created_at TEXT NOT NULL
+);
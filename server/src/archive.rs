@@ -0,0 +1,132 @@
+use axum::{
+  Json,
+  extract::{Path, Query, State},
+  http::StatusCode,
+  response::IntoResponse,
+};
+use chrono::NaiveDate;
+use nixdle::{
+  api::{AttemptData, GiveUpData, StartData},
+  game::Game,
+};
+
+use crate::{AppState, HOST, attempt, db, give_up, session::Kind};
+
+type Error = (StatusCode, &'static str);
+
+/// lists the days that can be replayed, newest first
+pub async fn list_handler(State(state): State<AppState>) -> Result<impl IntoResponse, Error> {
+  let today = current_date(&state);
+  let dates = db::dates_before(&state.db, today).await.map_err(|_| {
+    (
+      StatusCode::INTERNAL_SERVER_ERROR,
+      "couldn't read the archive",
+    )
+  })?;
+
+  Ok(Json(
+    dates
+      .iter()
+      .map(|d| d.format("%Y-%m-%d").to_string())
+      .collect::<Vec<_>>(),
+  ))
+}
+
+pub async fn start_handler(
+  State(state): State<AppState>,
+  Path(date): Path<String>,
+  Query(data): Query<StartData>,
+) -> Result<impl IntoResponse, Error> {
+  let game = archived_game(&state, &date).await?;
+  std::thread::sleep(std::time::Duration::from_millis(1000));
+
+  let date = game.get_date();
+  let session = state
+    .sessions
+    .resume_or_issue(data.session, Kind::Archive, &date);
+  let attempt_url = format!("{}/archive/{}/attempt", HOST, date);
+  let giveup_url = format!("{}/archive/{}/giveup", HOST, date);
+  let game_state = state.game.read().unwrap();
+  Ok(Json(game_state.start(
+    &game,
+    attempt_url,
+    giveup_url,
+    session,
+  )))
+}
+
+pub async fn attempt_handler(
+  State(state): State<AppState>,
+  Path(date): Path<String>,
+  Json(data): Json<AttemptData>,
+) -> Result<impl IntoResponse, Error> {
+  let game = archived_game(&state, &date).await?;
+  if game.get_date() != data.date {
+    return Err((StatusCode::CONFLICT, "the attempt is for another game"));
+  }
+
+  let response = attempt(
+    &state,
+    &state.game.read().unwrap(),
+    Kind::Archive,
+    &game,
+    &data,
+  )?;
+  std::thread::sleep(std::time::Duration::from_millis(500));
+  Ok(Json(response))
+}
+
+pub async fn giveup_handler(
+  State(state): State<AppState>,
+  Path(date): Path<String>,
+  Json(data): Json<GiveUpData>,
+) -> Result<impl IntoResponse, Error> {
+  let game = archived_game(&state, &date).await?;
+  if game.get_date() != data.date {
+    return Err((StatusCode::CONFLICT, "that's another game"));
+  }
+
+  let reveal = give_up(
+    &state,
+    &state.game.read().unwrap(),
+    Kind::Archive,
+    &game,
+    &data,
+  )?;
+  Ok(Json(reveal))
+}
+
+/// date of the game being played right now
+fn current_date(state: &AppState) -> NaiveDate {
+  let game = state.game.read().unwrap();
+  game
+    .game
+    .as_ref()
+    .expect("where game??")
+    .get_date()
+    .parse()
+    .expect("games have valid dates")
+}
+
+/// loads the game of a day that's already over
+async fn archived_game(state: &AppState, date: &str) -> Result<Game, Error> {
+  let date: NaiveDate = date.parse().map_err(|_| {
+    (
+      StatusCode::BAD_REQUEST,
+      "that's not a date (try YYYY-MM-DD)",
+    )
+  })?;
+  if date >= current_date(state) {
+    return Err((StatusCode::NOT_FOUND, "that day isn't over yet"));
+  }
+
+  db::load(&state.db, date)
+    .await
+    .map_err(|_| {
+      (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "couldn't read the archive",
+      )
+    })?
+    .ok_or((StatusCode::NOT_FOUND, "there's no game for that day"))
+}
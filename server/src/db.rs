@@ -0,0 +1,185 @@
+use nixdle::game::Game;
+use sqlx::{
+  SqlitePool,
+  sqlite::SqliteConnectOptions,
+  types::{Json, chrono::NaiveDate},
+};
+use std::str::FromStr;
+
+/// opens the database (creating it if missing) & runs pending migrations
+pub async fn open(url: &str) -> Result<SqlitePool, sqlx::Error> {
+  let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+  let pool = SqlitePool::connect_with(options).await?;
+  sqlx::migrate!().run(&pool).await?;
+  Ok(pool)
+}
+
+/// loads the game stored for a day
+pub async fn load(pool: &SqlitePool, date: NaiveDate) -> Result<Option<Game>, sqlx::Error> {
+  sqlx::query_as("SELECT * FROM games WHERE date = ?")
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+}
+
+/// lists the days with a stored game before the given one, newest first
+pub async fn dates_before(
+  pool: &SqlitePool,
+  date: NaiveDate,
+) -> Result<Vec<NaiveDate>, sqlx::Error> {
+  sqlx::query_scalar("SELECT date FROM games WHERE date < ? ORDER BY date DESC")
+    .bind(date)
+    .fetch_all(pool)
+    .await
+}
+
+/// stores a game, keeping the existing one if the day already has a game
+pub async fn insert(pool: &SqlitePool, game: &Game) -> Result<(), sqlx::Error> {
+  let (inputs, output) = game.get_types();
+  sqlx::query(
+    "INSERT INTO games
+      (date, func, description, args, inputs, output,
+       nix_commit, nix_version, data_generated_at, seed, created_at)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT (date) DO NOTHING",
+  )
+  .bind(game.get_date())
+  .bind(game.get_func())
+  .bind(game.get_description())
+  .bind(game.get_args_count())
+  .bind(Json(inputs))
+  .bind(Json(output))
+  .bind(game.get_nix_commit())
+  .bind(game.get_nix_version())
+  .bind(game.get_data_generated_at())
+  .bind(game.get_seed())
+  .bind(game.get_created_at())
+  .execute(pool)
+  .await?;
+  Ok(())
+}
+
+/// loads the game stored for a day, generating & storing one if there's none yet
+pub async fn load_or_insert(
+  pool: &SqlitePool,
+  date: NaiveDate,
+  generate: impl FnOnce() -> Game,
+) -> Result<Game, sqlx::Error> {
+  if let Some(game) = load(pool, date).await? {
+    return Ok(game);
+  }
+
+  insert(pool, &generate()).await?;
+  // another instance might've been faster, theirs wins
+  load(pool, date).await?.ok_or(sqlx::Error::RowNotFound)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use nixdle::{function::Type, provenance::Provenance};
+  use tempfile::TempDir;
+
+  fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+  }
+
+  fn game(func: &str, date: NaiveDate) -> Game {
+    let provenance = Provenance {
+      nixpkgs_rev: "d792a6e".to_string(),
+      nix_version: "2.32.0".to_string(),
+      generated_at: None,
+    };
+    Game::new(
+      func.to_string(),
+      "does things".to_string(),
+      2,
+      (
+        vec![Type::String, Type::List(Box::new(Type::Any))],
+        Type::Bool,
+      ),
+      42,
+      date,
+      &provenance,
+    )
+  }
+
+  async fn temp_db() -> (TempDir, SqlitePool) {
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!("sqlite://{}", dir.path().join("nixdle.db").display());
+    let pool = open(&url).await.unwrap();
+    (dir, pool)
+  }
+
+  #[tokio::test]
+  async fn load_missing_game() {
+    let (_dir, pool) = temp_db().await;
+
+    assert!(load(&pool, date()).await.unwrap().is_none());
+  }
+
+  #[tokio::test]
+  async fn insert_and_load_game() {
+    let (_dir, pool) = temp_db().await;
+    let stored = game("lib.flip", date());
+    insert(&pool, &stored).await.unwrap();
+
+    let loaded = load(&pool, date()).await.unwrap().unwrap();
+    assert_eq!(loaded.get_func(), "lib.flip");
+    assert_eq!(loaded.get_date(), "2025-01-01");
+    assert_eq!(loaded.get_seed(), 42);
+    assert_eq!(loaded.get_args_count(), 2);
+    assert!(loaded.get_types() == stored.get_types());
+    assert_eq!(loaded.get_nix_commit(), "d792a6e");
+    assert_eq!(loaded.get_created_at(), stored.get_created_at());
+  }
+
+  #[tokio::test]
+  async fn load_or_insert_keeps_stored_game() {
+    let (_dir, pool) = temp_db().await;
+
+    let first = load_or_insert(&pool, date(), || game("lib.flip", date()))
+      .await
+      .unwrap();
+    let second = load_or_insert(&pool, date(), || panic!("should be stored"))
+      .await
+      .unwrap();
+    assert_eq!(first.get_func(), second.get_func());
+
+    insert(&pool, &game("lib.id", date())).await.unwrap();
+    let loaded = load(&pool, date()).await.unwrap().unwrap();
+    assert_eq!(loaded.get_func(), "lib.flip");
+  }
+
+  #[tokio::test]
+  async fn dates_before_skips_today() {
+    let (_dir, pool) = temp_db().await;
+    for day in 1..=3 {
+      let date = NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+      insert(&pool, &game("lib.flip", date)).await.unwrap();
+    }
+
+    let dates = dates_before(&pool, NaiveDate::from_ymd_opt(2025, 1, 3).unwrap())
+      .await
+      .unwrap();
+    assert_eq!(
+      dates,
+      [
+        NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn reopen_keeps_games() {
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!("sqlite://{}", dir.path().join("nixdle.db").display());
+
+    insert(&open(&url).await.unwrap(), &game("lib.flip", date()))
+      .await
+      .unwrap();
+    let loaded = load(&open(&url).await.unwrap(), date()).await.unwrap();
+    assert!(loaded.is_some());
+  }
+}
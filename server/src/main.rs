@@ -1,16 +1,51 @@
 use axum::{
   Json, Router,
-  extract::State,
+  extract::{Query, State},
+  http::StatusCode,
   response::IntoResponse,
   routing::{get, post},
 };
-use std::{env, fs};
+use chrono::FixedOffset;
+use std::{
+  env, fs,
+  sync::{Arc, RwLock},
+};
+
+use nixdle::{
+  State as GameState,
+  api::{
+    AttemptData, AttemptResponse, Completion, GiveUpData, ROLLED_OVER, RevealMessage, StartData,
+    VerifyData,
+  },
+  game::Game,
+  parse_builtin_types, parse_functions_filtered, parse_provenance,
+  provenance::Provenance,
+};
+
+mod archive;
+mod db;
+mod practice;
+mod rollover;
+mod session;
+mod token;
 
-use nixdle::{State as GameState, api::AttemptData, parse_builtin_types, parse_functions_filtered};
+use session::{Kind, Sessions};
+use token::Signer;
 
 const HOSTNAME: &str = "0.0.0.0:8000";
 const HOST: &str = "http://0.0.0.0:8000";
 
+/// game state shared between requests & the rollover task
+type SharedState = Arc<RwLock<GameState>>;
+
+#[derive(Clone)]
+struct AppState {
+  game: SharedState,
+  sessions: Sessions,
+  db: sqlx::SqlitePool,
+  signer: Signer,
+}
+
 #[tokio::main]
 async fn main() {
   println!(
@@ -19,6 +54,21 @@ async fn main() {
   let data_dir = env::var("DATA_DIR").unwrap_or_else(|_| "lib/data".to_string());
   #[cfg(debug_assertions)]
   println!("using {} as data dir", data_dir);
+  let salt = env::var("SALT").unwrap_or_else(|_| {
+    println!("WARNING!! SALT is not set, anyone can work out future games");
+    String::new()
+  });
+  let signer = match env::var("SECRET") {
+    Ok(secret) => Signer::new(&secret),
+    Err(_) => {
+      println!("WARNING!! SECRET is not set, wins won't verify after a restart");
+      Signer::random()
+    }
+  };
+  let database_url = env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite://nixdle.db".to_string());
+  let offset: FixedOffset = env::var("UTC_OFFSET")
+    .map(|o| o.parse().expect("UTC_OFFSET should look like +02:00"))
+    .unwrap_or_else(|_| FixedOffset::east_opt(0).unwrap());
 
   let builtin_types =
     parse_builtin_types(&fs::read_to_string(format!("{}/builtin_types.json", data_dir)).unwrap())
@@ -29,33 +79,405 @@ async fn main() {
   )
   .unwrap();
 
-  let mut game_state = GameState::new(functions, builtin_types);
-  game_state.init_random_game();
+  let provenance = match fs::read_to_string(format!("{}/manifest.json", data_dir)) {
+    Ok(manifest) => parse_provenance(&manifest).unwrap(),
+    Err(_) => {
+      println!("WARNING!! no manifest.json in data dir, dataset provenance is unknown");
+      Provenance::default()
+    }
+  };
+  #[cfg(debug_assertions)]
+  println!(
+    "dataset from nixpkgs {} & nix {}",
+    provenance.nixpkgs_rev, provenance.nix_version
+  );
+
+  let db = db::open(&database_url)
+    .await
+    .expect("couldn't open the database");
+
+  let mut game_state = GameState::new(functions, builtin_types, provenance);
+  let today = rollover::today(&offset);
+  let game = db::load_or_insert(&db, today, || game_state.daily_game(today, &salt))
+    .await
+    .expect("couldn't load today's game");
 
-  println!("initialized new game");
-  println!("{}", game_state.game.clone().unwrap().get_func());
+  println!("initialized game for {} (seed {})", today, game.get_seed());
+  println!("{}", game.get_func());
+  game_state.game = Some(game);
+
+  let app_state = AppState {
+    game: Arc::new(RwLock::new(game_state)),
+    sessions: Sessions::default(),
+    db,
+    signer,
+  };
+  tokio::spawn(rollover::run(app_state.clone(), offset, salt));
 
   let app = Router::new()
     .route("/", get(|| async { "hai :3" }))
     .route("/start", get(start_handler))
     .route("/attempt", post(attempt_handler))
-    .with_state(game_state);
+    .route("/giveup", post(giveup_handler))
+    .route("/functions", get(functions_handler))
+    .route("/verify", post(verify_handler))
+    .route("/archive", get(archive::list_handler))
+    .route("/archive/{date}/start", get(archive::start_handler))
+    .route("/archive/{date}/attempt", post(archive::attempt_handler))
+    .route("/archive/{date}/giveup", post(archive::giveup_handler))
+    .route("/practice/start", get(practice::start_handler))
+    .route("/practice/attempt", post(practice::attempt_handler))
+    .route("/practice/giveup", post(practice::giveup_handler))
+    .with_state(app_state);
 
   println!("listening on http://{}", HOSTNAME);
   let listener = tokio::net::TcpListener::bind(HOSTNAME).await.unwrap();
   axum::serve(listener, app).await.unwrap();
 }
 
-async fn start_handler(State(state): State<GameState>) -> impl IntoResponse {
+async fn start_handler(
+  State(state): State<AppState>,
+  Query(data): Query<StartData>,
+) -> impl IntoResponse {
   std::thread::sleep(std::time::Duration::from_millis(1000));
-  Json(state.start_game(format!("{}/attempt", HOST)))
+  let game = state.game.read().unwrap();
+  let date = game.game.as_ref().expect("where game??").get_date();
+  let session = state
+    .sessions
+    .resume_or_issue(data.session, Kind::Daily, &date);
+  Json(game.start_game(
+    format!("{}/attempt", HOST),
+    format!("{}/giveup", HOST),
+    session,
+  ))
 }
 
 async fn attempt_handler(
-  State(state): State<GameState>,
+  State(state): State<AppState>,
   Json(data): Json<AttemptData>,
 ) -> impl IntoResponse {
-  let response = state.attempt_game(&data.input, data.attempts);
+  let response = {
+    let game = state.game.read().unwrap();
+    let current = game.game.as_ref().expect("where game??");
+    if current.get_date() != data.date {
+      return Err((StatusCode::CONFLICT, ROLLED_OVER));
+    }
+
+    attempt(&state, &game, Kind::Daily, current, &data)?
+  };
   std::thread::sleep(std::time::Duration::from_millis(500));
-  Json(response)
+  Ok(Json(response))
+}
+
+async fn giveup_handler(
+  State(state): State<AppState>,
+  Json(data): Json<GiveUpData>,
+) -> impl IntoResponse {
+  let game = state.game.read().unwrap();
+  let current = game.game.as_ref().expect("where game??");
+  if current.get_date() != data.date {
+    return Err((StatusCode::CONFLICT, ROLLED_OVER));
+  }
+
+  Ok(Json(give_up(&state, &game, Kind::Daily, current, &data)?))
+}
+
+/// every path that can be guessed, for completion
+async fn functions_handler(State(state): State<AppState>) -> impl IntoResponse {
+  Json(state.game.read().unwrap().paths().to_vec())
+}
+
+/// checks a completion token & tells what it vouches for
+async fn verify_handler(
+  State(state): State<AppState>,
+  Json(data): Json<VerifyData>,
+) -> Result<Json<Completion>, (StatusCode, &'static str)> {
+  state
+    .signer
+    .verify(&data.token)
+    .map(Json)
+    .ok_or((StatusCode::UNAUTHORIZED, "that token wasn't signed here"))
+}
+
+/// judges an attempt at a daily or archived game, counting it towards the session
+fn attempt(
+  state: &AppState,
+  game_state: &GameState,
+  kind: Kind,
+  game: &Game,
+  data: &AttemptData,
+) -> Result<AttemptResponse, (StatusCode, &'static str)> {
+  let date = game.get_date();
+  let attempts = state
+    .sessions
+    .with(&data.session, |session| {
+      if session.kind != kind || session.date != date {
+        return Err((StatusCode::CONFLICT, "the session is for another game"));
+      }
+      if session.finished {
+        return Err((StatusCode::CONFLICT, "the game is over"));
+      }
+      Ok(session.attempts)
+    })
+    .ok_or((StatusCode::UNAUTHORIZED, "unknown session"))??;
+
+  // judging can take a while, so the other sessions aren't kept waiting meanwhile
+  let mut response = game_state.attempt(game, &data.input, attempts);
+  let AttemptResponse::Attempt(msg) = &mut response else {
+    return Ok(response);
+  };
+
+  state
+    .sessions
+    .with(&data.session, |session| {
+      // another attempt might have ended the game in the meantime
+      if session.finished {
+        return Err((StatusCode::CONFLICT, "the game is over"));
+      }
+      session.attempts += 1;
+      session.finished = msg.success;
+
+      // replays are played with the answer a day old, they prove nothing
+      if msg.success && kind == Kind::Daily {
+        msg.token = Some(state.signer.sign(&Completion {
+          date: date.clone(),
+          session: data.session.clone(),
+          attempts: session.attempts,
+          solved_at: chrono::Utc::now(),
+        }));
+      }
+      Ok(())
+    })
+    .ok_or((StatusCode::UNAUTHORIZED, "unknown session"))??;
+  Ok(response)
+}
+
+/// reveals the answer of a daily or archived game, ending the session
+fn give_up(
+  state: &AppState,
+  game_state: &GameState,
+  kind: Kind,
+  game: &Game,
+  data: &GiveUpData,
+) -> Result<RevealMessage, (StatusCode, &'static str)> {
+  let date = game.get_date();
+  state
+    .sessions
+    .with(&data.session, |session| {
+      if session.kind != kind || session.date != date {
+        return Err((StatusCode::CONFLICT, "the session is for another game"));
+      }
+
+      session.finished = true;
+      Ok(game_state.reveal(game))
+    })
+    .ok_or((StatusCode::UNAUTHORIZED, "unknown session"))?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::NaiveDate;
+  use nixdle::function::{Function, Type};
+
+  const DATE: &str = "2025-01-01";
+
+  fn app_state() -> AppState {
+    let functions: Vec<Function> = serde_json::from_str(
+      r#"[
+        {
+          "meta": { "path": ["lib", "strings", "hasPrefix"], "signature": "hasPrefix :: string -> string -> bool" },
+          "content": { "content": "checks a prefix" }
+        },
+        {
+          "meta": { "path": ["lib", "flip"], "signature": "flip :: (a -> b -> c) -> b -> a -> c" },
+          "content": { "content": "flips" }
+        }
+      ]"#,
+    )
+    .unwrap();
+
+    let mut game_state = GameState::new(functions, Vec::new(), Provenance::default());
+    game_state.game = Some(Game::new(
+      "lib.strings.hasPrefix".to_string(),
+      "checks a prefix".to_string(),
+      2,
+      (vec![Type::String, Type::String], Type::Bool),
+      0,
+      NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+      &Provenance::default(),
+    ));
+
+    AppState {
+      game: Arc::new(RwLock::new(game_state)),
+      sessions: Sessions::default(),
+      db: sqlx::SqlitePool::connect_lazy("sqlite::memory:").unwrap(),
+      signer: Signer::new("secret"),
+    }
+  }
+
+  fn send(
+    state: &AppState,
+    session: &str,
+    input: &str,
+  ) -> Result<AttemptResponse, (StatusCode, &'static str)> {
+    send_as(state, Kind::Daily, session, input)
+  }
+
+  fn send_as(
+    state: &AppState,
+    kind: Kind,
+    session: &str,
+    input: &str,
+  ) -> Result<AttemptResponse, (StatusCode, &'static str)> {
+    let game_state = state.game.read().unwrap();
+    let data = AttemptData {
+      input: input.to_string(),
+      session: session.to_string(),
+      date: DATE.to_string(),
+    };
+    attempt(
+      state,
+      &game_state,
+      kind,
+      game_state.game.as_ref().unwrap(),
+      &data,
+    )
+  }
+
+  fn attempts(state: &AppState, session: &str) -> usize {
+    state.sessions.with(session, |s| s.attempts).unwrap()
+  }
+
+  #[tokio::test]
+  async fn count_only_judged_attempts() {
+    let state = app_state();
+    let session = state.sessions.resume_or_issue(None, Kind::Daily, DATE);
+
+    let response = send(&state, &session, "lib.notAThing").unwrap();
+    assert!(matches!(response, AttemptResponse::Unknown(_)));
+    assert_eq!(attempts(&state, &session), 0);
+
+    let response = send(&state, &session, "lib.flip").unwrap();
+    assert!(matches!(response, AttemptResponse::Attempt(_)));
+    assert_eq!(attempts(&state, &session), 1);
+  }
+
+  #[tokio::test]
+  async fn clues_follow_the_server_count() {
+    let state = app_state();
+    let session = state.sessions.resume_or_issue(None, Kind::Daily, DATE);
+
+    let clues = (0..=nixdle::NEXT_CLUE_ATTEMPTS)
+      .map(|_| match send(&state, &session, "lib.flip").unwrap() {
+        AttemptResponse::Attempt(msg) => msg.clues.len(),
+        _ => panic!("lib.flip should be judged"),
+      })
+      .collect::<Vec<_>>();
+
+    assert!(clues[..nixdle::NEXT_CLUE_ATTEMPTS].iter().all(|c| *c == 0));
+    assert_eq!(clues[nixdle::NEXT_CLUE_ATTEMPTS], 1);
+  }
+
+  #[tokio::test]
+  async fn reject_unknown_sessions() {
+    let state = app_state();
+
+    assert!(matches!(
+      send(&state, "made up", "lib.flip"),
+      Err((StatusCode::UNAUTHORIZED, _))
+    ));
+  }
+
+  #[tokio::test]
+  async fn reject_sessions_for_another_date() {
+    let state = app_state();
+    let session = state
+      .sessions
+      .resume_or_issue(None, Kind::Daily, "2024-12-31");
+
+    assert!(matches!(
+      send(&state, &session, "lib.flip"),
+      Err((StatusCode::CONFLICT, _))
+    ));
+    assert_eq!(attempts(&state, &session), 0);
+  }
+
+  #[tokio::test]
+  async fn reject_attempts_after_a_win() {
+    let state = app_state();
+    let session = state.sessions.resume_or_issue(None, Kind::Daily, DATE);
+
+    match send(&state, &session, "hasPrefix").unwrap() {
+      AttemptResponse::Attempt(msg) => assert!(msg.success),
+      _ => panic!("hasPrefix should be judged"),
+    }
+    assert!(matches!(
+      send(&state, &session, "lib.flip"),
+      Err((StatusCode::CONFLICT, "the game is over"))
+    ));
+    assert_eq!(attempts(&state, &session), 1);
+  }
+
+  #[tokio::test]
+  async fn tokens_carry_the_session() {
+    let state = app_state();
+    let session = state.sessions.resume_or_issue(None, Kind::Daily, DATE);
+
+    let AttemptResponse::Attempt(msg) = send(&state, &session, "hasPrefix").unwrap() else {
+      panic!("hasPrefix should be judged");
+    };
+    let completion = state.signer.verify(&msg.token.unwrap()).unwrap();
+    assert_eq!(completion.session, session);
+    assert_eq!(completion.date, DATE);
+    assert_eq!(completion.attempts, 1);
+  }
+
+  #[tokio::test]
+  async fn no_wins_after_giving_up() {
+    let state = app_state();
+    let session = state.sessions.resume_or_issue(None, Kind::Daily, DATE);
+
+    {
+      let game_state = state.game.read().unwrap();
+      let data = GiveUpData {
+        session: session.clone(),
+        date: DATE.to_string(),
+      };
+      assert!(
+        give_up(
+          &state,
+          &game_state,
+          Kind::Daily,
+          game_state.game.as_ref().unwrap(),
+          &data
+        )
+        .is_ok()
+      );
+    }
+    assert!(matches!(
+      send(&state, &session, "hasPrefix"),
+      Err((StatusCode::CONFLICT, "the game is over"))
+    ));
+  }
+
+  #[tokio::test]
+  async fn replays_are_not_signed() {
+    let state = app_state();
+    let session = state.sessions.resume_or_issue(None, Kind::Archive, DATE);
+
+    let AttemptResponse::Attempt(msg) =
+      send_as(&state, Kind::Archive, &session, "hasPrefix").unwrap()
+    else {
+      panic!("hasPrefix should be judged");
+    };
+    assert!(msg.success);
+    assert!(msg.token.is_none());
+
+    // nor can a replay session be passed off as a daily one
+    assert!(matches!(
+      send(&state, &session, "hasPrefix"),
+      Err((StatusCode::CONFLICT, _))
+    ));
+  }
 }
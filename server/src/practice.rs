@@ -0,0 +1,92 @@
+use axum::{
+  Json,
+  extract::{Query, State},
+  http::StatusCode,
+  response::IntoResponse,
+};
+use nixdle::api::{AttemptData, AttemptResponse, GiveUpData, StartData};
+
+use crate::{AppState, HOST};
+
+type Error = (StatusCode, &'static str);
+
+/// starts a practice session with a random game, or resumes one
+pub async fn start_handler(
+  State(state): State<AppState>,
+  Query(data): Query<StartData>,
+) -> impl IntoResponse {
+  std::thread::sleep(std::time::Duration::from_millis(1000));
+  let game_state = state.game.read().unwrap();
+  let date = game_state.game.as_ref().expect("where game??").get_date();
+
+  let (session, game) = state
+    .sessions
+    .resume_or_issue_practice(data.session, &date, || game_state.random_game());
+  Json(game_state.start(
+    &game,
+    format!("{}/practice/attempt", HOST),
+    format!("{}/practice/giveup", HOST),
+    session,
+  ))
+}
+
+pub async fn attempt_handler(
+  State(state): State<AppState>,
+  Json(data): Json<AttemptData>,
+) -> Result<impl IntoResponse, Error> {
+  let (game, attempts) = state
+    .sessions
+    .with(&data.session, |session| {
+      let game = session
+        .practice
+        .clone()
+        .ok_or((StatusCode::CONFLICT, "that's not a practice session"))?;
+      if session.finished {
+        return Err((StatusCode::CONFLICT, "the game is over"));
+      }
+      Ok((game, session.attempts))
+    })
+    .ok_or((StatusCode::UNAUTHORIZED, "unknown session"))??;
+
+  // judged without holding up the other sessions, like daily attempts
+  let response = state
+    .game
+    .read()
+    .unwrap()
+    .attempt(&game, &data.input, attempts);
+  if let AttemptResponse::Attempt(msg) = &response {
+    state
+      .sessions
+      .with(&data.session, |session| {
+        if session.finished {
+          return Err((StatusCode::CONFLICT, "the game is over"));
+        }
+        session.attempts += 1;
+        session.finished = msg.success;
+        Ok(())
+      })
+      .ok_or((StatusCode::UNAUTHORIZED, "unknown session"))??;
+  }
+  std::thread::sleep(std::time::Duration::from_millis(500));
+  Ok(Json(response))
+}
+
+pub async fn giveup_handler(
+  State(state): State<AppState>,
+  Json(data): Json<GiveUpData>,
+) -> Result<impl IntoResponse, Error> {
+  let game_state = state.game.read().unwrap();
+  let reveal = state
+    .sessions
+    .with(&data.session, |session| {
+      let game = session
+        .practice
+        .as_ref()
+        .ok_or((StatusCode::CONFLICT, "that's not a practice session"))?;
+
+      session.finished = true;
+      Ok(game_state.reveal(game))
+    })
+    .ok_or((StatusCode::UNAUTHORIZED, "unknown session"))??;
+  Ok(Json(reveal))
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, Utc};
+use std::time::Duration;
+
+use crate::{AppState, db};
+
+/// today's date in the given time zone
+pub fn today(offset: &FixedOffset) -> NaiveDate {
+  date_at(Utc::now(), offset)
+}
+
+/// the date at the given time in the given time zone
+fn date_at(now: DateTime<Utc>, offset: &FixedOffset) -> NaiveDate {
+  now.with_timezone(offset).date_naive()
+}
+
+/// time left until the next midnight in the given time zone
+fn until_midnight(offset: &FixedOffset) -> Duration {
+  until_midnight_at(Utc::now(), offset)
+}
+
+/// time left from the given time until the next midnight in the given time zone
+fn until_midnight_at(now: DateTime<Utc>, offset: &FixedOffset) -> Duration {
+  let now = now.with_timezone(offset);
+  let midnight = (now.date_naive() + Days::new(1))
+    .and_hms_opt(0, 0, 0)
+    .expect("midnight always exists")
+    .and_local_timezone(*offset)
+    .single()
+    .expect("fixed offsets are never ambiguous");
+
+  (midnight - now).to_std().unwrap_or_default()
+}
+
+/// swaps the daily game every midnight
+pub async fn run(state: AppState, offset: FixedOffset, salt: String) {
+  loop {
+    tokio::time::sleep(until_midnight(&offset)).await;
+
+    let date = today(&offset);
+    {
+      let state = state.game.read().unwrap();
+      if state.game.as_ref().map(|g| g.get_date()) == Some(date.format("%Y-%m-%d").to_string()) {
+        // woke up a bit too early
+        continue;
+      }
+    }
+
+    let generate = || state.game.read().unwrap().daily_game(date, &salt);
+    let game = match db::load_or_insert(&state.db, date, generate).await {
+      Ok(game) => game,
+      Err(e) => {
+        println!(
+          "WARNING!! couldn't store the game, it won't survive a restart: {}",
+          e
+        );
+        generate()
+      }
+    };
+
+    println!("rolled over to {} (seed {})", date, game.get_seed());
+    #[cfg(debug_assertions)]
+    println!("{}", game.get_func());
+
+    state.sessions.prune(&game.get_date());
+    state.game.write().unwrap().game = Some(game);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn at(time: &str) -> DateTime<Utc> {
+    time.parse().unwrap()
+  }
+
+  #[test]
+  fn date_in_other_time_zones() {
+    let ahead: FixedOffset = "+02:00".parse().unwrap();
+    let behind: FixedOffset = "-05:00".parse().unwrap();
+
+    assert_eq!(
+      date_at(at("2025-01-01T23:30:00Z"), &ahead),
+      NaiveDate::from_ymd_opt(2025, 1, 2).unwrap()
+    );
+    assert_eq!(
+      date_at(at("2025-01-01T03:00:00Z"), &behind),
+      NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()
+    );
+  }
+
+  #[test]
+  fn midnight_in_other_time_zones() {
+    let ahead: FixedOffset = "+02:00".parse().unwrap();
+    let behind: FixedOffset = "-05:00".parse().unwrap();
+
+    assert_eq!(
+      until_midnight_at(at("2025-01-01T21:30:00Z"), &ahead),
+      Duration::from_secs(30 * 60)
+    );
+    assert_eq!(
+      until_midnight_at(at("2025-01-01T03:00:00Z"), &behind),
+      Duration::from_secs(2 * 60 * 60)
+    );
+  }
+}
@@ -0,0 +1,254 @@
+use nixdle::game::Game;
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
+
+/// sessions without a single attempt are dropped after being unused for this long
+const UNUSED_FOR: Duration = Duration::from_secs(60 * 60);
+/// at most this many sessions are kept, the least recently used ones go first
+const MAX_SESSIONS: usize = 100_000;
+
+/// which game a session is for
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+  /// today's game, over at the rollover
+  Daily,
+  /// an earlier day's game
+  Archive,
+  /// a random game of the session's own
+  Practice,
+}
+
+/// a player's progress on a single game
+pub struct Session {
+  pub kind: Kind,
+  /// date of the game the session was issued for
+  pub date: String,
+  /// number of valid attempts made so far
+  pub attempts: usize,
+  /// the session's own game, for practice
+  pub practice: Option<Game>,
+  /// the game was solved or given up on, no more attempts
+  pub finished: bool,
+  /// when the session was last issued or played
+  used: Instant,
+}
+
+impl Session {
+  fn new(kind: Kind, date: &str, practice: Option<Game>) -> Self {
+    Session {
+      kind,
+      date: date.to_string(),
+      attempts: 0,
+      practice,
+      finished: false,
+      used: Instant::now(),
+    }
+  }
+}
+
+/// sessions by token
+#[derive(Clone, Default)]
+pub struct Sessions(Arc<Mutex<HashMap<String, Session>>>);
+
+impl Sessions {
+  /// resumes the given session if it's still valid for the kind & date or issues a new one
+  /// returns the session token
+  pub fn resume_or_issue(&self, token: Option<String>, kind: Kind, date: &str) -> String {
+    let mut sessions = self.0.lock().unwrap();
+
+    if let Some(token) = token
+      && sessions
+        .get(&token)
+        .is_some_and(|s| s.kind == kind && s.date == date && s.practice.is_none())
+    {
+      return token;
+    }
+
+    let token = new_token();
+    make_room(&mut sessions, MAX_SESSIONS, Instant::now());
+    sessions.insert(token.clone(), Session::new(kind, date, None));
+    token
+  }
+
+  /// resumes the given practice session or issues a new one with a game from `issue`
+  /// returns the session token & its game
+  pub fn resume_or_issue_practice(
+    &self,
+    token: Option<String>,
+    date: &str,
+    issue: impl FnOnce() -> Game,
+  ) -> (String, Game) {
+    let mut sessions = self.0.lock().unwrap();
+
+    if let Some(token) = token
+      && let Some(game) = sessions.get(&token).and_then(|s| s.practice.clone())
+    {
+      return (token, game);
+    }
+
+    let token = new_token();
+    let game = issue();
+    make_room(&mut sessions, MAX_SESSIONS, Instant::now());
+    sessions.insert(
+      token.clone(),
+      Session::new(Kind::Practice, date, Some(game.clone())),
+    );
+    (token, game)
+  }
+
+  /// runs `f` on the session with the given token
+  /// returns None if there's no such session
+  pub fn with<T>(&self, token: &str, f: impl FnOnce(&mut Session) -> T) -> Option<T> {
+    self.0.lock().unwrap().get_mut(token).map(|session| {
+      session.used = Instant::now();
+      f(session)
+    })
+  }
+
+  /// drops daily sessions not issued for the given date
+  /// other sessions don't end with the day, they're only dropped once they're unused
+  pub fn prune(&self, date: &str) {
+    let now = Instant::now();
+    self.0.lock().unwrap().retain(|_, s| match s.kind {
+      Kind::Daily => s.date == date,
+      Kind::Archive | Kind::Practice => now - s.used < UNUSED_FOR,
+    });
+  }
+}
+
+/// makes room for a new session when there are `max` already
+/// unused sessions past their time go first, then the least recently used ones
+fn make_room(sessions: &mut HashMap<String, Session>, max: usize, now: Instant) {
+  if sessions.len() < max {
+    return;
+  }
+
+  sessions.retain(|_, s| s.attempts > 0 || now - s.used < UNUSED_FOR);
+  while sessions.len() >= max {
+    let Some(oldest) = sessions
+      .iter()
+      .min_by_key(|(_, s)| s.used)
+      .map(|(token, _)| token.clone())
+    else {
+      break;
+    };
+    sessions.remove(&oldest);
+  }
+}
+
+fn new_token() -> String {
+  format!("{:032x}", rand::random::<u128>())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn game() -> Game {
+    Game::new(
+      "lib.flip".to_string(),
+      "flips".to_string(),
+      3,
+      (vec![], nixdle::function::Type::Any),
+      0,
+      chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+      &nixdle::provenance::Provenance::default(),
+    )
+  }
+
+  #[test]
+  fn resume_for_same_date_only() {
+    let sessions = Sessions::default();
+    let token = sessions.resume_or_issue(None, Kind::Daily, "2025-01-01");
+
+    assert_eq!(
+      sessions.resume_or_issue(Some(token.clone()), Kind::Daily, "2025-01-01"),
+      token
+    );
+    assert_ne!(
+      sessions.resume_or_issue(Some(token.clone()), Kind::Daily, "2025-01-02"),
+      token
+    );
+    assert_ne!(
+      sessions.resume_or_issue(Some("made up".to_string()), Kind::Daily, "2025-01-01"),
+      "made up"
+    );
+  }
+
+  #[test]
+  fn practice_sessions_are_not_daily_ones() {
+    let sessions = Sessions::default();
+    let (token, _) = sessions.resume_or_issue_practice(None, "2025-01-01", game);
+
+    assert_ne!(
+      sessions.resume_or_issue(Some(token.clone()), Kind::Daily, "2025-01-01"),
+      token
+    );
+  }
+
+  #[test]
+  fn archive_sessions_are_not_daily_ones() {
+    let sessions = Sessions::default();
+    let token = sessions.resume_or_issue(None, Kind::Archive, "2025-01-01");
+
+    assert_ne!(
+      sessions.resume_or_issue(Some(token.clone()), Kind::Daily, "2025-01-01"),
+      token
+    );
+    assert_eq!(
+      sessions.resume_or_issue(Some(token.clone()), Kind::Archive, "2025-01-01"),
+      token
+    );
+  }
+
+  #[test]
+  fn prune_only_daily_sessions() {
+    let sessions = Sessions::default();
+    let replay = sessions.resume_or_issue(None, Kind::Archive, "2024-12-25");
+    let (practice, _) = sessions.resume_or_issue_practice(None, "2025-01-01", game);
+
+    sessions.prune("2025-01-02");
+    assert!(sessions.with(&replay, |_| ()).is_some());
+    assert!(sessions.with(&practice, |_| ()).is_some());
+  }
+
+  #[test]
+  fn prune_other_dates() {
+    let sessions = Sessions::default();
+    let old = sessions.resume_or_issue(None, Kind::Daily, "2025-01-01");
+    let new = sessions.resume_or_issue(None, Kind::Daily, "2025-01-02");
+
+    sessions.prune("2025-01-02");
+    assert!(sessions.with(&old, |_| ()).is_none());
+    assert!(sessions.with(&new, |_| ()).is_some());
+  }
+
+  #[test]
+  fn make_room_drops_unused_then_least_recent() {
+    let start = Instant::now();
+    let now = start + UNUSED_FOR * 3;
+    let session = |attempts, used| Session {
+      attempts,
+      used,
+      ..Session::new(Kind::Daily, "2025-01-01", None)
+    };
+    let mut sessions = HashMap::new();
+    sessions.insert("stale".to_string(), session(0, start + UNUSED_FOR));
+    sessions.insert("played".to_string(), session(3, start));
+    sessions.insert("fresh".to_string(), session(0, now));
+
+    make_room(&mut sessions, 3, now);
+    assert!(!sessions.contains_key("stale"));
+    assert_eq!(sessions.len(), 2);
+
+    make_room(&mut sessions, 2, now);
+    assert!(!sessions.contains_key("played"));
+    assert!(sessions.contains_key("fresh"));
+
+    make_room(&mut sessions, 5, now);
+    assert_eq!(sessions.len(), 1);
+  }
+}
@@ -0,0 +1,101 @@
+//! signed tokens proving a game was solved, so wins can be trusted anywhere
+
+use base64::prelude::*;
+use hmac::{Hmac, Mac};
+use nixdle::api::Completion;
+use sha2::Sha256;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// signs & verifies completion tokens
+/// a token is the completion as json & its signature, both base64 & joined by a dot
+#[derive(Clone)]
+pub struct Signer {
+  key: Arc<[u8]>,
+}
+
+impl Signer {
+  pub fn new(secret: &str) -> Self {
+    Signer {
+      key: secret.as_bytes().into(),
+    }
+  }
+
+  /// a signer with a throwaway key, its tokens are worthless after a restart
+  pub fn random() -> Self {
+    Signer {
+      key: rand::random::<[u8; 32]>().into(),
+    }
+  }
+
+  pub fn sign(&self, completion: &Completion) -> String {
+    let payload = serde_json::to_vec(completion).expect("completions are always serializable");
+    let signature = self.mac(&payload).finalize().into_bytes();
+
+    format!(
+      "{}.{}",
+      BASE64_URL_SAFE_NO_PAD.encode(payload),
+      BASE64_URL_SAFE_NO_PAD.encode(signature)
+    )
+  }
+
+  /// returns the completion if the token was signed with this key
+  pub fn verify(&self, token: &str) -> Option<Completion> {
+    let (payload, signature) = token.split_once('.')?;
+    let payload = BASE64_URL_SAFE_NO_PAD.decode(payload).ok()?;
+    let signature = BASE64_URL_SAFE_NO_PAD.decode(signature).ok()?;
+
+    self.mac(&payload).verify_slice(&signature).ok()?;
+    serde_json::from_slice(&payload).ok()
+  }
+
+  fn mac(&self, payload: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(&self.key).expect("hmac takes keys of any size");
+    mac.update(payload);
+    mac
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn completion() -> Completion {
+    Completion {
+      date: "2025-01-01".to_string(),
+      session: "abc".to_string(),
+      attempts: 4,
+      solved_at: chrono::Utc::now(),
+    }
+  }
+
+  #[test]
+  fn roundtrip() {
+    let signer = Signer::new("secret");
+    let verified = signer.verify(&signer.sign(&completion())).unwrap();
+    assert_eq!(verified.date, "2025-01-01");
+    assert_eq!(verified.session, "abc");
+    assert_eq!(verified.attempts, 4);
+  }
+
+  #[test]
+  fn reject_forged() {
+    let signer = Signer::new("secret");
+    let token = signer.sign(&completion());
+
+    assert!(Signer::new("another secret").verify(&token).is_none());
+    assert!(Signer::random().verify(&token).is_none());
+
+    let (_, signature) = token.split_once('.').unwrap();
+    let mut forged = completion();
+    forged.attempts = 1;
+    let payload = BASE64_URL_SAFE_NO_PAD.encode(serde_json::to_vec(&forged).unwrap());
+    assert!(
+      signer
+        .verify(&format!("{}.{}", payload, signature))
+        .is_none()
+    );
+    assert!(signer.verify("garbage").is_none());
+  }
+}